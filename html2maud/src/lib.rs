@@ -0,0 +1,33 @@
+//! Converts HTML into Maud syntax.
+//!
+//! This is meant for moving existing pages and mockups over to Maud. The
+//! output uses the `.class` and `#id` shorthands, `;` for void elements, and
+//! string literals for text:
+//!
+//! ```rust
+//! let maud = html2maud::convert(r#"<div class="card"><p>Hello, <b>world</b>!</p><br></div>"#);
+//! assert_eq!(
+//!     maud,
+//!     r#".card {
+//!     p { "Hello, " b { "world" } "!" }
+//!     br;
+//! }"#,
+//! );
+//! ```
+//!
+//! Whitespace is tidied up along the way: runs of whitespace are collapsed,
+//! and whitespace next to block elements like `div` is removed. Whitespace in
+//! `pre` and `textarea` is kept as is. Comments are turned into Rust
+//! comments, so they won't show up in the rendered page.
+
+mod print;
+
+use maud_html as parse;
+
+/// Converts an HTML document or fragment into the body of an `html!` macro.
+///
+/// The parser is forgiving: it accepts unquoted attributes and left out end
+/// tags, and keeps anything it doesn't understand as text.
+pub fn convert(html: &str) -> String {
+    print::print(&parse::parse(html))
+}
@@ -0,0 +1,39 @@
+use std::{
+    env, fs,
+    io::{self, Read},
+    process,
+};
+
+const USAGE: &str = "\
+Converts HTML into Maud syntax.
+
+Usage: html2maud [FILE]
+
+Reads from standard input if no file is given.";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let input = match args.as_slice() {
+        [] => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map(|_| input)
+        }
+        [flag] if flag == "-h" || flag == "--help" => {
+            println!("{USAGE}");
+            return;
+        }
+        [path] => fs::read_to_string(path),
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    match input {
+        Ok(input) => println!("{}", html2maud::convert(&input)),
+        Err(error) => {
+            eprintln!("error: {error}");
+            process::exit(1);
+        }
+    }
+}
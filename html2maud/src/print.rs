@@ -0,0 +1,335 @@
+//! Writing a parsed document in Maud syntax.
+
+use std::fmt::Write;
+
+use maud_escape::INLINE_ELEMENTS;
+
+use crate::parse::{Attr, Element, Node, is_raw_text, is_void};
+
+pub fn print(nodes: &[Node]) -> String {
+    let mut printer = Printer {
+        output: String::new(),
+    };
+    for node in clean(nodes, false) {
+        printer.node(&node, 0, false);
+    }
+    printer.output
+}
+
+struct Printer {
+    output: String,
+}
+
+impl Printer {
+    fn line(&mut self, depth: usize) {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        for _ in 0..depth {
+            self.output.push_str("    ");
+        }
+    }
+
+    /// Writes a node on a line of its own.
+    ///
+    /// `preserve` is whether the node is inside an element like `pre`, where
+    /// whitespace has to be kept as it is.
+    fn node(&mut self, node: &Node, depth: usize, preserve: bool) {
+        match node {
+            Node::Element(element) if !is_valid_name(&element.name) => {
+                // Maud can't write this element, so keep its contents at least
+                self.line(depth);
+                let _ = write!(self.output, "// unsupported element: <{}>", element.name);
+                for child in clean(&element.children, preserve) {
+                    self.node(&child, depth, preserve);
+                }
+            }
+            Node::Element(element) => {
+                self.line(depth);
+                self.element(element, depth, preserve);
+            }
+            Node::Text(text) => {
+                self.line(depth);
+                self.text(text);
+            }
+            Node::Comment(comment) => {
+                for line in comment
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                {
+                    self.line(depth);
+                    self.output.push_str("// ");
+                    self.output.push_str(line);
+                }
+            }
+            Node::Doctype(doctype) => {
+                self.line(depth);
+                if doctype.eq_ignore_ascii_case("doctype html") {
+                    self.output.push_str("(maud::DOCTYPE)");
+                } else {
+                    self.pre_escaped(&format!("<!{doctype}>"));
+                }
+            }
+        }
+    }
+
+    /// Writes a node that's part of a line.
+    fn inline_node(&mut self, node: &Node, depth: usize, preserve: bool) {
+        match node {
+            Node::Element(element) => self.element(element, depth, preserve),
+            Node::Text(text) => self.text(text),
+            // Never inline
+            Node::Comment(_) | Node::Doctype(_) => unreachable!(),
+        }
+    }
+
+    fn element(&mut self, element: &Element, depth: usize, preserve: bool) {
+        let preserve = preserve || preserves_whitespace(&element.name);
+
+        let mut classes = Vec::new();
+        let mut id = None;
+        let mut attrs = Vec::new();
+        for attr in &element.attrs {
+            match (attr.name.as_str(), &attr.value) {
+                ("class", Some(value)) if !value.trim().is_empty() => {
+                    classes.extend(value.split_ascii_whitespace())
+                }
+                ("id", Some(value)) if !value.is_empty() => id = Some(value.as_str()),
+                _ => attrs.push(attr),
+            }
+        }
+
+        // A `div` with a class or ID can be written as just that
+        let has_name = element.name != "div" || (classes.is_empty() && id.is_none());
+        if has_name {
+            self.output.push_str(&element.name);
+        }
+        if let Some(id) = id {
+            // Since Rust 2021, `name#` is reserved syntax
+            if has_name {
+                self.output.push(' ');
+            }
+            self.output.push('#');
+            self.name(id);
+        }
+        for class in classes {
+            self.output.push('.');
+            self.name(class);
+        }
+        for attr in attrs {
+            self.attr(attr);
+        }
+
+        if is_void(&element.name) {
+            self.output.push(';');
+            return;
+        }
+
+        let children = clean(&element.children, preserve);
+        if children.is_empty() {
+            self.output.push_str(" {}");
+        } else if element.name == "script" || element.name == "style" {
+            self.output.push_str(" { ");
+            if let [Node::Text(text)] = children.as_slice() {
+                self.pre_escaped(text);
+            }
+            self.output.push_str(" }");
+        } else if children.iter().all(fits_on_line) {
+            self.output.push_str(" { ");
+            for (i, child) in children.iter().enumerate() {
+                if i > 0 {
+                    self.output.push(' ');
+                }
+                self.inline_node(child, depth, preserve);
+            }
+            self.output.push_str(" }");
+        } else {
+            self.output.push_str(" {");
+            for child in &children {
+                self.node(child, depth + 1, preserve);
+            }
+            self.line(depth);
+            self.output.push('}');
+        }
+    }
+
+    fn attr(&mut self, attr: &Attr) {
+        self.output.push(' ');
+        self.name(&attr.name);
+        if let Some(value) = &attr.value {
+            self.output.push('=');
+            self.string(value);
+        }
+    }
+
+    /// Writes a name, quoting it if it can't be written as is.
+    fn name(&mut self, name: &str) {
+        if is_valid_name(name) {
+            self.output.push_str(name);
+        } else {
+            self.string(name);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        self.string(text);
+    }
+
+    fn pre_escaped(&mut self, html: &str) {
+        self.output.push_str("(maud::PreEscaped(");
+        self.string(html);
+        self.output.push_str("))");
+    }
+
+    /// Writes a Rust string literal. Raw strings are used where they're
+    /// easier to read.
+    fn string(&mut self, value: &str) {
+        let needs_escapes = value.contains(['"', '\\']);
+        let can_be_raw = !value.contains("\"#") && !value.chars().any(needs_escape_in_raw);
+        if needs_escapes && can_be_raw {
+            let _ = write!(self.output, "r#\"{value}\"#");
+            return;
+        }
+
+        self.output.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                c if needs_escape_in_raw(c) => {
+                    let _ = write!(self.output, "\\u{{{:x}}}", c as u32);
+                }
+                c => self.output.push(c),
+            }
+        }
+        self.output.push('"');
+    }
+}
+
+/// Returns whether a character should be escaped, because it's invisible or
+/// easily mistaken for a space.
+fn needs_escape_in_raw(c: char) -> bool {
+    (c.is_control() && c != '\n')
+        || (c.is_whitespace() && !matches!(c, ' ' | '\n'))
+        || matches!(
+            c,
+            '\u{ad}' | '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}'
+        )
+}
+
+/// Returns whether a name can be written without quotes: that is, whether it
+/// consists of identifiers and numbers separated by `-` or `:`.
+fn is_valid_name(name: &str) -> bool {
+    name.split(['-', ':']).all(|fragment| {
+        let is_ident = fragment.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && fragment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && fragment != "_";
+        let is_number = !fragment.is_empty() && fragment.bytes().all(|b| b.is_ascii_digit());
+        is_ident || is_number
+    }) && name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+}
+
+fn preserves_whitespace(name: &str) -> bool {
+    name == "pre" || is_raw_text(name)
+}
+
+/// Returns whether a node can go on the same line as its siblings.
+fn fits_on_line(node: &Node) -> bool {
+    match node {
+        Node::Text(_) => true,
+        // The comment for an unsupported element needs a line of its own
+        Node::Element(element) if !is_valid_name(&element.name) => false,
+        Node::Element(element) => {
+            (INLINE_ELEMENTS.contains(&element.name.as_str()) || element.children.is_empty())
+                && !is_raw_text(&element.name)
+                && clean(&element.children, false).iter().all(fits_on_line)
+        }
+        Node::Comment(_) | Node::Doctype(_) => false,
+    }
+}
+
+fn is_inline(node: Option<&Node>) -> bool {
+    match node {
+        Some(Node::Text(_)) => true,
+        // Browsers treat elements they don't know as inline
+        Some(Node::Element(element)) => {
+            INLINE_ELEMENTS.contains(&element.name.as_str()) || !is_valid_name(&element.name)
+        }
+        _ => false,
+    }
+}
+
+/// Tidies up the whitespace in a list of nodes.
+///
+/// Runs of whitespace are collapsed into a single space, and removed entirely
+/// next to block elements, where they don't show up on the page.
+fn clean(nodes: &[Node], preserve_whitespace: bool) -> Vec<Node> {
+    if preserve_whitespace {
+        return nodes.to_vec();
+    }
+    let mut cleaned = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let Node::Text(text) = node else {
+            cleaned.push(node.clone());
+            continue;
+        };
+        let mut text = collapse_whitespace(text);
+        if !is_inline(i.checked_sub(1).and_then(|i| nodes.get(i))) {
+            text = text.trim_start_matches(' ').to_string();
+        }
+        if !is_inline(nodes.get(i + 1)) {
+            text = text.trim_end_matches(' ').to_string();
+        }
+        if !text.is_empty() {
+            cleaned.push(Node::Text(text));
+        }
+    }
+    cleaned
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_whitespace {
+                output.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            output.push(c);
+            in_whitespace = false;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_valid_name;
+
+    #[test]
+    fn names() {
+        for name in [
+            "div",
+            "my-element",
+            "data-x",
+            "xml:lang",
+            "h1",
+            "aria-level-2",
+        ] {
+            assert!(is_valid_name(name), "{name}");
+        }
+        for name in [
+            "", "-x", "x-", "@click", ":class", "w-1/2", "2xl", "a.b", "_",
+        ] {
+            assert!(!is_valid_name(name), "{name}");
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Each test converts some HTML, checks that the result matches the Maud code
+//! given, then renders that code with `html!` and compares it to the HTML.
+
+use maud::html;
+use proc_macro2::TokenStream;
+
+macro_rules! round_trip {
+    ($html:expr, { $($maud:tt)* }) => {
+        round_trip!($html, { $($maud)* }, $html)
+    };
+    ($html:expr, { $($maud:tt)* }, $rendered:expr) => {{
+        let converted = html2maud::convert($html);
+        assert_eq!(
+            tokens(&converted),
+            tokens(stringify!($($maud)*)),
+            "converted to:\n{converted}",
+        );
+        assert_eq!(html! { $($maud)* }.into_string(), $rendered);
+    }};
+}
+
+fn tokens(code: &str) -> String {
+    code.parse::<TokenStream>().unwrap().to_string()
+}
+
+#[test]
+fn elements() {
+    round_trip!(
+        r#"<div class="card featured" id="main"><h1>Title</h1><p>Some <b>bold</b> text</p></div>"#,
+        {
+            #main.card.featured {
+                h1 { "Title" }
+                p { "Some " b { "bold" } " text" }
+            }
+        },
+        r#"<div class="card featured" id="main"><h1>Title</h1><p>Some <b>bold</b> text</p></div>"#
+    );
+}
+
+#[test]
+fn ids() {
+    round_trip!(
+        r#"<section id="intro" class="a b"><input id="q" aria-label="Search"></section>"#,
+        {
+            section #intro.a.b {
+                input #q aria-label="Search";
+            }
+        },
+        r#"<section class="a b" id="intro"><input id="q" aria-label="Search"></section>"#
+    );
+}
+
+#[test]
+fn void_elements() {
+    round_trip!(
+        r#"<p>a<br>b</p><img src="a.png" alt=""><input type="checkbox" aria-label="Agree" checked>"#,
+        {
+            p { "a" br; "b" }
+            img src="a.png" alt="";
+            input type="checkbox" aria-label="Agree" checked;
+        }
+    );
+}
+
+#[test]
+fn whitespace() {
+    round_trip!(
+        "
+        <ul>
+            <li>One</li>
+            <li>Two  <em>and</em>
+                three</li>
+        </ul>
+        <pre>  keep
+   this  </pre>
+        ",
+        {
+            ul {
+                li { "One" }
+                li { "Two " em { "and" } " three" }
+            }
+            pre { "  keep\n   this  " }
+        },
+        "<ul><li>One</li><li>Two <em>and</em> three</li></ul><pre>  keep\n   this  </pre>"
+    );
+}
+
+#[test]
+fn text_escaping() {
+    round_trip!(
+        r#"<p title="Say &quot;hi&quot;">Fish &amp; Chips &lt;3&nbsp;&copy;</p>"#,
+        {
+            p title=r#"Say "hi""# { "Fish & Chips <3\u{a0}©" }
+        },
+        "<p title=\"Say &quot;hi&quot;\">Fish &amp; Chips &lt;3\u{a0}©</p>"
+    );
+}
+
+#[test]
+fn unusual_names() {
+    round_trip!(
+        r#"<div class="w-1/2 md:flex" @click="go" data-id="1"></div><my-widget></my-widget>"#,
+        {
+            ."w-1/2".md:flex "@click"="go" data-id="1" {}
+            my-widget {}
+        }
+    );
+}
+
+#[test]
+fn unsupported_elements() {
+    round_trip!(
+        "<p>a <x.y>b</x.y> c</p>",
+        {
+            p {
+                "a "
+                "b"
+                " c"
+            }
+        },
+        "<p>a b c</p>"
+    );
+    assert_eq!(
+        html2maud::convert("<p>a <x.y></x.y> c</p>"),
+        "p {\n    \"a \"\n    // unsupported element: <x.y>\n    \" c\"\n}"
+    );
+}
+
+#[test]
+fn scripts_and_doctype() {
+    round_trip!(
+        "<!DOCTYPE html><html><head><title>A &amp; B</title><script>if (a < b) {}</script></head></html>",
+        {
+            (maud::DOCTYPE)
+            html {
+                head {
+                    title { "A & B" }
+                    script { (maud::PreEscaped("if (a < b) {}")) }
+                }
+            }
+        },
+        "<!DOCTYPE html><html><head><title>A &amp; B</title><script>if (a < b) {}</script></head></html>"
+    );
+}
+
+#[test]
+fn implied_end_tags() {
+    round_trip!(
+        "<ul><li>One<li>Two</ul><table><tr><td>a<td>b</table>",
+        {
+            ul {
+                li { "One" }
+                li { "Two" }
+            }
+            table {
+                tr {
+                    td { "a" }
+                    td { "b" }
+                }
+            }
+        },
+        "<ul><li>One</li><li>Two</li></ul><table><tr><td>a</td><td>b</td></tr></table>"
+    );
+}
+
+#[test]
+fn comments() {
+    round_trip!(
+        "<div><!-- Navigation --><nav></nav></div>",
+        {
+            div {
+                // Navigation
+                nav {}
+            }
+        },
+        "<div><nav></nav></div>"
+    );
+}
@@ -0,0 +1,102 @@
+#![feature(test)]
+
+extern crate test;
+
+use std::fmt::Write;
+
+use maud::{Escaper, html};
+
+/// The escaper that Maud used before, which handles a byte at a time.
+fn escape_bytewise(input: &str, output: &mut String) {
+    for b in input.bytes() {
+        match b {
+            b'&' => output.push_str("&amp;"),
+            b'<' => output.push_str("&lt;"),
+            b'>' => output.push_str("&gt;"),
+            b'"' => output.push_str("&quot;"),
+            _ => unsafe { output.as_mut_vec().push(b) },
+        }
+    }
+}
+
+/// About 10 KB of prose, with nothing to escape.
+fn plain_text() -> String {
+    "Maud Pie is an earth pony who lives on a rock farm with her family. She speaks in a \
+     monotone, and is known for her love of rocks, especially her pet rock Boulder. "
+        .repeat(64)
+}
+
+/// Prose with a character to escape every few dozen bytes.
+fn some_markup() -> String {
+    "Maud said: \"Rocks & minerals are different.\" Then she wrote <em>a poem</em> about it. "
+        .repeat(128)
+}
+
+/// Text that's mostly characters to escape.
+fn mostly_markup() -> String {
+    "<<>>&&\"\"<a>".repeat(1024)
+}
+
+fn bench_escaper(b: &mut test::Bencher, input: String) {
+    let input = test::black_box(input);
+    b.bytes = input.len() as u64;
+    b.iter(|| {
+        let mut output = String::with_capacity(input.len() * 2);
+        Escaper::new(&mut output).write_str(&input).unwrap();
+        output
+    });
+}
+
+fn bench_bytewise(b: &mut test::Bencher, input: String) {
+    let input = test::black_box(input);
+    b.bytes = input.len() as u64;
+    b.iter(|| {
+        let mut output = String::with_capacity(input.len() * 2);
+        escape_bytewise(&input, &mut output);
+        output
+    });
+}
+
+#[bench]
+fn plain_text_escaper(b: &mut test::Bencher) {
+    bench_escaper(b, plain_text());
+}
+
+#[bench]
+fn plain_text_bytewise(b: &mut test::Bencher) {
+    bench_bytewise(b, plain_text());
+}
+
+#[bench]
+fn some_markup_escaper(b: &mut test::Bencher) {
+    bench_escaper(b, some_markup());
+}
+
+#[bench]
+fn some_markup_bytewise(b: &mut test::Bencher) {
+    bench_bytewise(b, some_markup());
+}
+
+#[bench]
+fn mostly_markup_escaper(b: &mut test::Bencher) {
+    bench_escaper(b, mostly_markup());
+}
+
+#[bench]
+fn mostly_markup_bytewise(b: &mut test::Bencher) {
+    bench_bytewise(b, mostly_markup());
+}
+
+#[bench]
+fn render_text_heavy_page(b: &mut test::Bencher) {
+    let paragraphs = test::black_box(vec![plain_text(); 8]);
+    b.iter(|| {
+        html! {
+            article {
+                @for paragraph in &paragraphs {
+                    p { (paragraph) }
+                }
+            }
+        }
+    });
+}
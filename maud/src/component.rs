@@ -0,0 +1,128 @@
+use alloc::string::String;
+
+use crate::{Markup, PreEscaped, Render};
+
+/// A reusable piece of markup that can be invoked from `html!` with
+/// element-like syntax.
+///
+/// A component is a struct whose fields are its props. Invoking it with
+/// `@Name prop=value { ... }` constructs the struct from the given props, and
+/// passes the block to it as [`Children`]. The block can define named slots
+/// with `@slot name { ... }`.
+///
+/// Like [`Render`], an implementation must override at least one of
+/// `.render()` or `.render_to()`.
+///
+/// # Example
+///
+/// ```rust
+/// use maud::{Children, Component, Markup, html};
+///
+/// struct Card {
+///     title: &'static str,
+/// }
+///
+/// impl Component for Card {
+///     fn render(&self, children: &Children) -> Markup {
+///         html! {
+///             .card {
+///                 h2 { (self.title) }
+///                 (children)
+///                 @if children.has_slot("footer") {
+///                     footer { (children.slot("footer")) }
+///                 }
+///             }
+///         }
+///     }
+/// }
+///
+/// let markup = html! {
+///     @Card title="Ponies" {
+///         p { "Ponies are small horses." }
+///         @slot footer { "Photo credit: Applejack" }
+///     }
+/// };
+/// assert_eq!(
+///     markup.into_string(),
+///     concat!(
+///         r#"<div class="card"><h2>Ponies</h2><p>Ponies are small horses.</p>"#,
+///         r#"<footer>Photo credit: Applejack</footer></div>"#,
+///     ),
+/// );
+/// ```
+pub trait Component {
+    /// Renders `self` as a block of `Markup`, with the given children.
+    fn render(&self, children: &Children) -> Markup {
+        let mut buffer = String::new();
+        self.render_to(children, &mut buffer);
+        PreEscaped(buffer)
+    }
+
+    /// Appends a representation of `self` to the given buffer.
+    ///
+    /// Its default implementation just calls `.render()`, but you may
+    /// override it with something more efficient.
+    fn render_to(&self, children: &Children, buffer: &mut String) {
+        buffer.push_str(&self.render(children).into_string());
+    }
+}
+
+type RenderFn<'a> = &'a dyn Fn(&mut String);
+
+/// The block passed to a [`Component`].
+///
+/// Rendering this value (for example, by splicing it with `(children)`)
+/// renders the contents of the block, apart from any named slots.
+#[derive(Clone, Copy)]
+pub struct Children<'a> {
+    body: Option<RenderFn<'a>>,
+    slots: &'a [(&'a str, RenderFn<'a>)],
+}
+
+impl<'a> Children<'a> {
+    #[doc(hidden)]
+    pub fn new(body: Option<RenderFn<'a>>, slots: &'a [(&'a str, RenderFn<'a>)]) -> Children<'a> {
+        Children { body, slots }
+    }
+
+    /// Returns an empty set of children, for invoking a component outside of
+    /// `html!`.
+    pub fn empty() -> Children<'static> {
+        Children {
+            body: None,
+            slots: &[],
+        }
+    }
+
+    /// Returns whether the block is missing or empty, ignoring named slots.
+    pub fn is_empty(&self) -> bool {
+        self.body.is_none()
+    }
+
+    /// Returns whether a slot with the given name was defined.
+    pub fn has_slot(&self, name: &str) -> bool {
+        self.slots.iter().any(|(slot, _)| *slot == name)
+    }
+
+    /// Returns the contents of the slot with the given name.
+    ///
+    /// If there is no such slot, the result is empty.
+    pub fn slot(&self, name: &str) -> Children<'a> {
+        Children {
+            body: self
+                .slots
+                .iter()
+                .find(|(slot, _)| *slot == name)
+                .map(|(_, body)| *body),
+            slots: &[],
+        }
+    }
+}
+
+impl Render for Children<'_> {
+    fn render_to(&self, buffer: &mut String) {
+        if let Some(body) = self.body {
+            body(buffer);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Context-aware escaping for splices.
+//!
+//! Plain HTML escaping is enough for text and ordinary attribute values, but
+//! not for a value that lands inside a `<script>` element, a URL attribute, or
+//! an event handler. The `html!` macro tracks where each splice appears, and
+//! renders it with [`Render::render_to_context`] using one of the
+//! [`Context`]s below.
+//!
+//! Text-like types such as [`str`] and [`String`] escape their contents to suit
+//! the context. Other types, including [`PreEscaped`] and the output of
+//! `html!`, are treated as trusted markup and passed through unchanged.
+//!
+//! # Example
+//!
+//! ```rust
+//! use maud::html;
+//!
+//! let name = "</script><script>alert(1)</script>";
+//! let homepage = "javascript:alert(1)";
+//! let markup = html! {
+//!     script { "const name = " (name) ";" }
+//!     a href=(homepage) { "Homepage" }
+//! };
+//! assert_eq!(
+//!     markup.into_string(),
+//!     concat!(
+//!         r#"<script>const name = '\u003c/script\u003e\u003cscript\u003ealert(1)\u003c/script\u003e';</script>"#,
+//!         r#"<a href="about:invalid#maud-unsafe-url">Homepage</a>"#,
+//!     ),
+//! );
+//! ```
+//!
+//! [`Render::render_to_context`]: crate::Render::render_to_context
+//! [`PreEscaped`]: crate::PreEscaped
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{EscapePolicy, escape};
+
+/// The place in a document where a splice appears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Context {
+    /// Element content, or the value of an ordinary attribute.
+    Html,
+    /// JavaScript code outside of a string literal, as in a `<script>` element
+    /// or an `on*` event handler attribute.
+    Script,
+    /// The inside of a JavaScript string literal.
+    ScriptString,
+    /// CSS code, as in a `<style>` element or a `style` attribute.
+    Style,
+    /// The start of a URL-valued attribute, such as `href` or `src`.
+    Url,
+    /// Element content, or the value of an ordinary attribute, escaped with
+    /// the given policy instead of the default. The `html!` macro uses this
+    /// for a splice marked with `#[escape(...)]`.
+    Policy(EscapePolicy),
+}
+
+impl Context {
+    /// Returns the context for the value of the attribute with the given name.
+    pub fn for_attribute(name: &str) -> Context {
+        match escape::AttributeKind::of(name) {
+            escape::AttributeKind::Text => Context::Html,
+            escape::AttributeKind::Url => Context::Url,
+            escape::AttributeKind::Style => Context::Style,
+            escape::AttributeKind::Script => Context::Script,
+        }
+    }
+}
+
+/// The URL substituted for one with a disallowed scheme.
+pub const UNSAFE_URL: &str = "about:invalid#maud-unsafe-url";
+
+/// Escapes a string for the given context, appending the result to `output`.
+pub fn escape_to_string(input: &str, context: Context, output: &mut String) {
+    match context {
+        Context::Html => escape::escape_to_string(input, output),
+        Context::Script => {
+            // Single quotes can be used in both elements and (double-quoted)
+            // attributes without any further escaping
+            output.push('\'');
+            escape_js_string(input, output);
+            output.push('\'');
+        }
+        Context::ScriptString => escape_js_string(input, output),
+        Context::Style => escape_css(input, output),
+        Context::Url => {
+            if is_safe_url(input) {
+                escape::escape_to_string(input, output);
+            } else {
+                output.push_str(UNSAFE_URL);
+            }
+        }
+        Context::Policy(policy) => policy.escape_to_string(input, output),
+    }
+}
+
+/// Escapes a string for use inside a JavaScript string literal.
+///
+/// Quotes, backslashes, line terminators and the HTML special characters are
+/// replaced with `\uXXXX` escape sequences. The result is safe to use in any
+/// kind of JavaScript string literal (including template literals), and since
+/// it never contains `<`, `&` or quotes, in both `<script>` elements and event
+/// handler attributes.
+pub fn escape_js_string(input: &str, output: &mut String) {
+    for c in input.chars() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '"' | '\'' | '`' | '$' | '<' | '>' | '&' | '\u{2028}' | '\u{2029}' => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c if c.is_ascii_control() => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+}
+
+/// Escapes a string for use in CSS.
+///
+/// ASCII letters, digits, spaces and the characters `#`, `%`, `,`, `-`, `.`,
+/// `:`, `;` and `_` are passed through; everything else in the ASCII range is
+/// replaced with a `\HH ` escape. This keeps values like `1px solid #fff` and
+/// declarations like `color: red; margin: 0` intact, while preventing the
+/// value from closing a block, starting a comment or calling a function.
+pub fn escape_css(input: &str, output: &mut String) {
+    for c in input.chars() {
+        match c {
+            'a'..='z'
+            | 'A'..='Z'
+            | '0'..='9'
+            | ' '
+            | '#'
+            | '%'
+            | ','
+            | '-'
+            | '.'
+            | ':'
+            | ';'
+            | '_' => output.push(c),
+            c if c.is_ascii() => {
+                let _ = write!(output, "\\{:x} ", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+}
+
+/// Returns whether the given URL is safe to use in a link.
+///
+/// Relative URLs are always allowed. Absolute URLs are allowed only if they
+/// use the `http`, `https`, `mailto` or `tel` scheme; in particular,
+/// `javascript:` and `data:` URLs are rejected.
+pub fn is_safe_url(url: &str) -> bool {
+    let url = url.trim_start_matches(|c: char| c.is_ascii_whitespace() || c.is_ascii_control());
+    let Some(colon) = url.find(':') else {
+        return true;
+    };
+    let scheme = &url[..colon];
+    if scheme.contains(['/', '?', '#']) {
+        // The colon is part of the path, query or fragment
+        return true;
+    }
+    ["http", "https", "mailto", "tel"]
+        .iter()
+        .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+}
+
+#[cfg(test)]
+mod test {
+    extern crate alloc;
+
+    use super::{Context, escape_to_string, is_safe_url};
+    use crate::EscapePolicy;
+    use alloc::string::String;
+
+    fn escape(input: &str, context: Context) -> String {
+        let mut s = String::new();
+        escape_to_string(input, context, &mut s);
+        s
+    }
+
+    #[test]
+    fn script() {
+        assert_eq!(
+            escape("</script>'\"\\", Context::Script),
+            r#"'\u003c/script\u003e\u0027\u0022\\'"#
+        );
+        assert_eq!(escape("a\nb", Context::ScriptString), r"a\nb");
+    }
+
+    #[test]
+    fn style() {
+        assert_eq!(escape("1px solid #fff", Context::Style), "1px solid #fff");
+        assert_eq!(
+            escape("color: red; margin: 0", Context::Style),
+            "color: red; margin: 0"
+        );
+        assert_eq!(
+            escape("red;}</style>", Context::Style),
+            r"red;\7d \3c \2f style\3e "
+        );
+        assert_eq!(
+            escape("background: url(/*x*/)", Context::Style),
+            r"background: url\28 \2f \2a x\2a \2f \29 "
+        );
+    }
+
+    #[test]
+    fn policies() {
+        let input = r#"<a title="it's">"#;
+        for (policy, expected) in [
+            (EscapePolicy::Text, r#"&lt;a title="it's"&gt;"#),
+            (
+                EscapePolicy::DoubleQuotedAttr,
+                "&lt;a title=&quot;it's&quot;&gt;",
+            ),
+            (
+                EscapePolicy::SingleQuotedAttr,
+                r#"&lt;a title="it&#39;s"&gt;"#,
+            ),
+            (EscapePolicy::Strict, "&lt;a title=&quot;it&#39;s&quot;&gt;"),
+        ] {
+            assert_eq!(escape(input, Context::Policy(policy)), expected);
+        }
+        assert_eq!(
+            escape(input, Context::Html),
+            escape(input, Context::Policy(EscapePolicy::default()))
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        assert_eq!(Context::for_attribute("HREF"), Context::Url);
+        assert_eq!(Context::for_attribute("style"), Context::Style);
+        assert_eq!(Context::for_attribute("onClick"), Context::Script);
+        assert_eq!(Context::for_attribute("on"), Context::Html);
+        assert_eq!(Context::for_attribute("one-two"), Context::Html);
+        assert_eq!(Context::for_attribute("data-href"), Context::Html);
+    }
+
+    #[test]
+    fn urls() {
+        assert!(is_safe_url("https://example.com/"));
+        assert!(is_safe_url("/relative/path?q=a:b"));
+        assert!(is_safe_url("MAILTO:pinkie@example.com"));
+        assert!(!is_safe_url("javascript:alert(1)"));
+        assert!(!is_safe_url(" \tJavaScript:alert(1)"));
+        assert!(!is_safe_url("data:text/html,<script>"));
+        assert_eq!(
+            escape("https://example.com/?a=1&b=2", Context::Url),
+            "https://example.com/?a=1&amp;b=2"
+        );
+        assert_eq!(escape("vbscript:msgbox", Context::Url), super::UNSAFE_URL);
+    }
+}
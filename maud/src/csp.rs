@@ -0,0 +1,281 @@
+//! Nonces for pages served with a Content Security Policy.
+//!
+//! A strict [Content Security Policy][csp] blocks inline `<script>` and
+//! `<style>` elements, unless they have a `nonce` attribute that matches the
+//! one in the `Content-Security-Policy` header. The nonce must be new for
+//! each response, so that an attacker can't guess it.
+//!
+//! With the `csp` feature enabled, `html!` adds the current [`Nonce`] to
+//! every `script` and `style` element. Make a nonce for each request, render
+//! the page inside [`Nonce::scope`], then send the nonce along with the page
+//! in the header:
+//!
+//! ```rust
+//! use maud::{csp::Nonce, html};
+//!
+//! let nonce = Nonce::random();
+//! let page = nonce.scope(|| {
+//!     html! {
+//!         style { "p { color: brown; }" }
+//!         script { "console.log('Hello, world!');" }
+//!     }
+//! });
+//! assert_eq!(
+//!     page.into_string(),
+//!     format!(
+//!         "<style nonce=\"{0}\">p {{ color: brown; }}</style>\
+//!          <script nonce=\"{0}\">console.log('Hello, world!');</script>",
+//!         nonce.as_str(),
+//!     ),
+//! );
+//! assert_eq!(
+//!     nonce.header_value(),
+//!     format!("script-src 'nonce-{0}'; style-src 'nonce-{0}'", nonce.as_str()),
+//! );
+//! ```
+//!
+//! With the `axum` feature, returning a `Nonce` alongside the page, as in
+//! `(nonce, page)`, sets the header. With the `actix-web` feature, `&Nonce`
+//! can be passed to `insert_header`.
+//!
+//! An element that already has a `nonce` attribute, whether written out or
+//! from an `..(attrs)` spread, is left alone. The nonce is stored in a
+//! thread-local variable, so it's only added by templates rendered on the
+//! same thread, inside the call to `scope`. An `html_stream!` template is
+//! rendered later, as the stream is polled, so it keeps the nonce that was
+//! current when the stream was made: create the stream inside `scope`, and it
+//! uses that nonce wherever it ends up running.
+//!
+//! [csp]: https://developer.mozilla.org/en-US/docs/Web/HTTP/CSP
+
+extern crate std;
+
+use alloc::{format, string::String, sync::Arc};
+use core::{cell::RefCell, fmt};
+
+use crate::Render;
+
+/// A random value that allows inline scripts and styles to run under a
+/// Content Security Policy.
+///
+/// A nonce only contains the characters of Base64, so it's safe to use
+/// anywhere in a page or header without escaping.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Nonce(Arc<str>);
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<Nonce>> = const { RefCell::new(None) };
+}
+
+impl Nonce {
+    /// Generates a nonce from 128 random bits, using the operating system's
+    /// random number generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the random number generator fails.
+    pub fn random() -> Nonce {
+        let mut bytes = [0; 16];
+        getrandom::fill(&mut bytes).expect("failed to generate a random nonce");
+        Nonce(base64_url(&bytes).into())
+    }
+
+    /// Creates a nonce from a value that was made elsewhere, such as by a
+    /// middleware that sets the header itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is empty, or isn't Base64 or URL-safe
+    /// Base64.
+    pub fn new(value: impl Into<String>) -> Result<Nonce, InvalidNonce> {
+        let value = value.into();
+        let data = value.trim_end_matches('=');
+        if !data.is_empty()
+            && value.len() - data.len() <= 2
+            && data
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'-' | b'_'))
+        {
+            Ok(Nonce(value.into()))
+        } else {
+            Err(InvalidNonce(value))
+        }
+    }
+
+    /// Returns the nonce that `html!` is adding to elements on this thread,
+    /// if any.
+    pub fn current() -> Option<Nonce> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Calls a function with this as the current nonce, so that templates
+    /// rendered by the function add it to their `script` and `style`
+    /// elements.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        /// Puts back the previous nonce, even if `f` panics.
+        struct Restore(Option<Nonce>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|current| current.replace(Some(self.clone()))));
+        f()
+    }
+
+    /// Returns the nonce itself.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the nonce as a CSP source expression, as in
+    /// `'nonce-abc123'`, for building a policy by hand.
+    pub fn source(&self) -> String {
+        format!("'nonce-{}'", self.0)
+    }
+
+    /// Returns a `Content-Security-Policy` header value that allows scripts
+    /// and styles with this nonce, and no others.
+    pub fn header_value(&self) -> String {
+        let source = self.source();
+        format!("script-src {source}; style-src {source}")
+    }
+}
+
+/// The error returned by [`Nonce::new`] for a value that isn't Base64.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidNonce(String);
+
+impl fmt::Display for InvalidNonce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid nonce {:?}; a nonce must be Base64", self.0)
+    }
+}
+
+impl std::error::Error for InvalidNonce {}
+
+/// Renders the nonce itself, for templates that add it by hand.
+impl Render for Nonce {
+    fn render_to(&self, buffer: &mut String) {
+        buffer.push_str(&self.0);
+    }
+}
+
+/// Writes a `nonce` attribute with the given nonce.
+pub(crate) fn write_nonce_attr(nonce: &Nonce, buffer: &mut String) {
+    buffer.push_str(" nonce=\"");
+    buffer.push_str(&nonce.0);
+    buffer.push('"');
+}
+
+/// Encodes bytes as URL-safe Base64, without padding.
+fn base64_url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            output.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    output
+}
+
+#[cfg(feature = "axum")]
+mod axum_support {
+    use super::Nonce;
+    use axum_core::response::{IntoResponseParts, ResponseParts};
+    use core::convert::Infallible;
+    use http::{HeaderValue, header};
+
+    /// Sets the `Content-Security-Policy` header to
+    /// [`Nonce::header_value`].
+    impl IntoResponseParts for Nonce {
+        type Error = Infallible;
+
+        fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Infallible> {
+            let value = HeaderValue::try_from(self.header_value())
+                .expect("a nonce is always a valid header value");
+            res.headers_mut()
+                .insert(header::CONTENT_SECURITY_POLICY, value);
+            Ok(res)
+        }
+    }
+}
+
+#[cfg(feature = "actix-web")]
+mod actix_support {
+    use super::Nonce;
+    use actix_web_dep::http::header::{
+        self, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderPair,
+    };
+
+    /// Makes a `Content-Security-Policy` header with
+    /// [`Nonce::header_value`].
+    impl TryIntoHeaderPair for &Nonce {
+        type Error = InvalidHeaderValue;
+
+        fn try_into_pair(self) -> Result<(HeaderName, HeaderValue), InvalidHeaderValue> {
+            Ok((
+                header::CONTENT_SECURITY_POLICY,
+                HeaderValue::try_from(self.header_value())?,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use super::{Nonce, base64_url};
+
+    #[test]
+    fn base64() {
+        assert_eq!(base64_url(b""), "");
+        assert_eq!(base64_url(b"M"), "TQ");
+        assert_eq!(base64_url(b"Ma"), "TWE");
+        assert_eq!(base64_url(b"Mau"), "TWF1");
+        assert_eq!(base64_url(b"\xfb\xff\xfe"), "-__-");
+        assert_eq!(base64_url(&[0; 16]).len(), 22);
+    }
+
+    #[test]
+    fn random() {
+        let nonce = Nonce::random();
+        assert_eq!(nonce.as_str().len(), 22);
+        assert_ne!(nonce, Nonce::random());
+        assert_eq!(Nonce::new(nonce.as_str()), Ok(nonce));
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(Nonce::new("aB3+/w==").unwrap().as_str(), "aB3+/w==");
+        for invalid in ["", "==", "a b", "a'", "a;b", "a==="] {
+            assert!(Nonce::new(invalid).is_err(), "{invalid:?}");
+        }
+        assert_eq!(
+            Nonce::new("a b").unwrap_err().to_string(),
+            r#"invalid nonce "a b"; a nonce must be Base64"#
+        );
+    }
+
+    #[test]
+    fn scope() {
+        let (a, b) = (Nonce::new("a").unwrap(), Nonce::new("b").unwrap());
+        assert_eq!(Nonce::current(), None);
+        a.scope(|| {
+            assert_eq!(Nonce::current(), Some(a.clone()));
+            b.scope(|| assert_eq!(Nonce::current(), Some(b.clone())));
+            assert_eq!(Nonce::current(), Some(a.clone()));
+            let _ = std::panic::catch_unwind(|| b.scope(|| panic!()));
+            assert_eq!(Nonce::current(), Some(a.clone()));
+        });
+        assert_eq!(Nonce::current(), None);
+    }
+}
@@ -0,0 +1,340 @@
+//! Render dates and times from the [`chrono`] and [`time`] crates.
+//!
+//! With the `chrono` or `time` feature enabled, [`Render`] is implemented for
+//! the date and time types of that crate. They're rendered in the format used
+//! by the `datetime` attribute of a `<time>` element, which is a subset of
+//! ISO 8601:
+//!
+//! * Dates are written as `2025-02-02`.
+//! * Times are written as `09:30:00`, with milliseconds added if there are
+//!   any (`09:30:00.250`).
+//! * Date-times join the two with a `T`, followed by `Z` for UTC or an offset
+//!   like `+10:00` if they have one.
+//!
+//! To show a date to a human, wrap it in [`Time`], which renders a whole
+//! `<time>` element with a format of your choosing.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[cfg(feature = "chrono")] {
+//! use chrono::NaiveDate;
+//! use maud::{datetime::Time, html};
+//!
+//! let released = NaiveDate::from_ymd_opt(2025, 2, 2).unwrap();
+//! let markup = html! {
+//!     p { "Released on " (Time::new(released).format("%B %-d, %Y")) }
+//!     meta itemprop="datePublished" content=(released);
+//! };
+//! assert_eq!(
+//!     markup.into_string(),
+//!     concat!(
+//!         r#"<p>Released on <time datetime="2025-02-02">February 2, 2025</time></p>"#,
+//!         r#"<meta itemprop="datePublished" content="2025-02-02">"#,
+//!     ),
+//! );
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{Render, context::Context};
+
+/// A date or time that can be rendered by [`Time`].
+///
+/// This is implemented for the date and time types of the [`chrono`] and
+/// [`time`] crates, when the feature of the same name is enabled.
+pub trait DateTime {
+    /// Appends `self` in the format used by the `datetime` attribute.
+    fn write_machine(&self, buffer: &mut String);
+
+    /// Appends `self` in the given human-readable format.
+    ///
+    /// The syntax of the format is up to the implementation.
+    ///
+    /// # Panics
+    ///
+    /// May panic if the format is invalid.
+    fn write_human(&self, format: &str, buffer: &mut String);
+}
+
+/// Renders a date or time as a `<time>` element.
+///
+/// The `datetime` attribute holds the value in a machine-readable format,
+/// while the content of the element is formatted for humans. Without a call
+/// to [`.format()`](Time::format), the content is the same as the attribute.
+///
+/// The format string uses the syntax of the crate that the value comes from:
+///
+/// * For [`chrono`], that's a [`strftime`-like
+///   format](https://docs.rs/chrono/0.4/chrono/format/strftime/index.html)
+///   such as `%B %-d, %Y`.
+/// * For [`time`], that's a [format
+///   description](https://time-rs.github.io/book/api/format-description.html)
+///   such as `[month repr:long] [day padding:none], [year]`.
+///
+/// # Panics
+///
+/// Rendering panics if the format string is invalid.
+#[derive(Debug, Clone, Copy)]
+pub struct Time<'a, T> {
+    value: T,
+    format: Option<&'a str>,
+}
+
+impl<T: DateTime> Time<'static, T> {
+    /// Wraps a date or time, showing it in the machine-readable format.
+    pub fn new(value: T) -> Time<'static, T> {
+        Time {
+            value,
+            format: None,
+        }
+    }
+}
+
+impl<T: DateTime> Time<'_, T> {
+    /// Sets the format to show the value in.
+    pub fn format(self, format: &str) -> Time<'_, T> {
+        Time {
+            value: self.value,
+            format: Some(format),
+        }
+    }
+}
+
+impl<T: DateTime> Render for Time<'_, T> {
+    fn render_to(&self, buffer: &mut String) {
+        // The machine-readable format never contains characters that need
+        // escaping
+        buffer.push_str("<time datetime=\"");
+        self.value.write_machine(buffer);
+        buffer.push_str("\">");
+        let mut text = String::new();
+        match self.format {
+            Some(format) => self.value.write_human(format, &mut text),
+            None => self.value.write_machine(&mut text),
+        }
+        crate::escape::escape_to_string(&text, buffer);
+        buffer.push_str("</time>");
+    }
+}
+
+/// Writes a value with `write_machine`, escaping it for the context.
+///
+/// In a `<script>`, for example, this quotes the value as a string.
+fn render_machine_to_context(value: &impl DateTime, context: Context, buffer: &mut String) {
+    let mut text = String::new();
+    value.write_machine(&mut text);
+    text.render_to_context(context, buffer);
+}
+
+fn write_date(year: i32, month: u8, day: u8, buffer: &mut String) {
+    let _ = write!(buffer, "{year:04}-{month:02}-{day:02}");
+}
+
+fn write_time(hour: u8, minute: u8, second: u8, nanosecond: u32, buffer: &mut String) {
+    let _ = write!(buffer, "{hour:02}:{minute:02}:{second:02}");
+    // A leap second is represented with more than a billion nanoseconds
+    let millisecond = (nanosecond / 1_000_000).min(999);
+    if millisecond > 0 {
+        let _ = write!(buffer, ".{millisecond:03}");
+    }
+}
+
+/// Writes a UTC offset in hours and minutes, which is as precise as the
+/// `datetime` attribute allows.
+fn write_offset(seconds: i32, buffer: &mut String) {
+    if seconds == 0 {
+        buffer.push('Z');
+    } else {
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let minutes = seconds.unsigned_abs() / 60;
+        let _ = write!(buffer, "{sign}{:02}:{:02}", minutes / 60, minutes % 60);
+    }
+}
+
+macro_rules! impl_render_with_date_time {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render_to(&self, buffer: &mut String) {
+                    self.write_machine(buffer);
+                }
+
+                fn render_to_context(&self, context: Context, buffer: &mut String) {
+                    render_machine_to_context(self, context, buffer);
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_support {
+    use alloc::string::String;
+    use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Timelike};
+    use core::fmt::{Display, Write};
+
+    use super::{DateTime, render_machine_to_context, write_date, write_offset, write_time};
+    use crate::{Render, context::Context};
+
+    fn write_chrono_date(date: &impl Datelike, buffer: &mut String) {
+        write_date(date.year(), date.month() as u8, date.day() as u8, buffer);
+    }
+
+    fn write_chrono_time(time: &impl Timelike, buffer: &mut String) {
+        write_time(
+            time.hour() as u8,
+            time.minute() as u8,
+            time.second() as u8,
+            time.nanosecond(),
+            buffer,
+        );
+    }
+
+    fn write_chrono_human(value: impl Display, buffer: &mut String) {
+        write!(buffer, "{value}").expect("invalid date format");
+    }
+
+    impl DateTime for NaiveDate {
+        fn write_machine(&self, buffer: &mut String) {
+            write_chrono_date(self, buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_chrono_human(self.format(format), buffer);
+        }
+    }
+
+    impl DateTime for NaiveTime {
+        fn write_machine(&self, buffer: &mut String) {
+            write_chrono_time(self, buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_chrono_human(self.format(format), buffer);
+        }
+    }
+
+    impl DateTime for NaiveDateTime {
+        fn write_machine(&self, buffer: &mut String) {
+            write_chrono_date(self, buffer);
+            buffer.push('T');
+            write_chrono_time(self, buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_chrono_human(self.format(format), buffer);
+        }
+    }
+
+    impl<Tz: TimeZone> DateTime for chrono::DateTime<Tz>
+    where
+        Tz::Offset: Display,
+    {
+        fn write_machine(&self, buffer: &mut String) {
+            self.naive_local().write_machine(buffer);
+            write_offset(self.offset().fix().local_minus_utc(), buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_chrono_human(self.format(format), buffer);
+        }
+    }
+
+    impl_render_with_date_time!(NaiveDate, NaiveTime, NaiveDateTime);
+
+    impl<Tz: TimeZone> Render for chrono::DateTime<Tz>
+    where
+        Tz::Offset: Display,
+    {
+        fn render_to(&self, buffer: &mut String) {
+            self.write_machine(buffer);
+        }
+
+        fn render_to_context(&self, context: Context, buffer: &mut String) {
+            render_machine_to_context(self, context, buffer);
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_support {
+    use alloc::string::String;
+    use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, format_description};
+
+    use super::{DateTime, render_machine_to_context, write_date, write_offset, write_time};
+    use crate::{Render, context::Context};
+
+    fn write_time_date(date: Date, buffer: &mut String) {
+        write_date(date.year(), date.month().into(), date.day(), buffer);
+    }
+
+    fn write_time_time(time: Time, buffer: &mut String) {
+        write_time(
+            time.hour(),
+            time.minute(),
+            time.second(),
+            time.nanosecond(),
+            buffer,
+        );
+    }
+
+    /// Formats a value with a format description parsed at runtime.
+    macro_rules! write_time_human {
+        ($value:expr, $format:expr, $buffer:expr) => {{
+            let format =
+                format_description::parse_borrowed::<2>($format).expect("invalid date format");
+            let text = $value.format(&format).expect("could not format date");
+            $buffer.push_str(&text);
+        }};
+    }
+
+    impl DateTime for Date {
+        fn write_machine(&self, buffer: &mut String) {
+            write_time_date(*self, buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_time_human!(self, format, buffer);
+        }
+    }
+
+    impl DateTime for Time {
+        fn write_machine(&self, buffer: &mut String) {
+            write_time_time(*self, buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_time_human!(self, format, buffer);
+        }
+    }
+
+    impl DateTime for PrimitiveDateTime {
+        fn write_machine(&self, buffer: &mut String) {
+            write_time_date(self.date(), buffer);
+            buffer.push('T');
+            write_time_time(self.time(), buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_time_human!(self, format, buffer);
+        }
+    }
+
+    impl DateTime for OffsetDateTime {
+        fn write_machine(&self, buffer: &mut String) {
+            write_time_date(self.date(), buffer);
+            buffer.push('T');
+            write_time_time(self.time(), buffer);
+            write_offset(self.offset().whole_seconds(), buffer);
+        }
+
+        fn write_human(&self, format: &str, buffer: &mut String) {
+            write_time_human!(self, format, buffer);
+        }
+    }
+
+    impl_render_with_date_time!(Date, Time, PrimitiveDateTime, OffsetDateTime);
+}
@@ -11,19 +11,39 @@
 
 extern crate alloc;
 
-use alloc::{borrow::Cow, boxed::Box, string::String};
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
 use core::fmt::{self, Arguments, Display, Write};
 
-pub use maud_macros::html;
+pub use maud_macros::{Render, html, html_to};
 
-mod escape;
+#[cfg(feature = "stream")]
+pub use maud_macros::html_stream;
 
-#[cfg(feature = "json")]
+mod component;
+pub mod context;
+mod pretty;
+mod text;
+
+pub use component::{Children, Component};
+
+use context::Context;
+use maud_escape as escape;
+
+#[cfg(feature = "std")]
 extern crate std;
 
 #[cfg(feature = "json")]
 pub mod json;
 
+#[cfg(feature = "csp")]
+pub mod csp;
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub mod datetime;
+
+#[cfg(feature = "stream")]
+pub mod stream;
+
 /// An adapter that escapes HTML special characters.
 ///
 /// The following characters are escaped:
@@ -33,10 +53,11 @@ pub mod json;
 /// * `>` is escaped as `&gt;`
 /// * `"` is escaped as `&quot;`
 ///
-/// All other characters are passed through unchanged.
+/// All other characters are passed through unchanged. To escape a different
+/// set of characters, use [`Escaper::with_policy`].
 ///
 /// **Note:** In versions prior to 0.13, the single quote (`'`) was
-/// escaped as well.
+/// escaped as well. [`EscapePolicy::Strict`] escapes it too.
 ///
 /// # Example
 ///
@@ -47,22 +68,111 @@ pub mod json;
 /// write!(Escaper::new(&mut s), "<script>launchMissiles()</script>").unwrap();
 /// assert_eq!(s, "&lt;script&gt;launchMissiles()&lt;/script&gt;");
 /// ```
-pub struct Escaper<'a>(&'a mut String);
+pub struct Escaper<'a> {
+    buffer: &'a mut String,
+    policy: EscapePolicy,
+}
 
 impl<'a> Escaper<'a> {
     /// Creates an `Escaper` from a `String`.
     pub fn new(buffer: &'a mut String) -> Escaper<'a> {
-        Escaper(buffer)
+        Escaper::with_policy(buffer, EscapePolicy::default())
+    }
+
+    /// Creates an `Escaper` that escapes the characters chosen by an
+    /// [`EscapePolicy`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use maud::{EscapePolicy, Escaper};
+    /// use std::fmt::Write;
+    /// let mut s = String::new();
+    /// write!(Escaper::with_policy(&mut s, EscapePolicy::Strict), "Maud's rock").unwrap();
+    /// assert_eq!(s, "Maud&#39;s rock");
+    /// ```
+    pub fn with_policy(buffer: &'a mut String, policy: EscapePolicy) -> Escaper<'a> {
+        Escaper { buffer, policy }
     }
 }
 
 impl fmt::Write for Escaper<'_> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        escape::escape_to_string(s, self.0);
+        self.policy.escape_to_string(s, self.buffer);
         Ok(())
     }
 }
 
+/// Which characters to escape, depending on where the text will end up.
+///
+/// | Policy               | `&` | `<` | `>` | `"` | `'` |
+/// |----------------------|-----|-----|-----|-----|-----|
+/// | [`Text`]             | ✓   | ✓   | ✓   |     |     |
+/// | [`DoubleQuotedAttr`] | ✓   | ✓   | ✓   | ✓   |     |
+/// | [`SingleQuotedAttr`] | ✓   | ✓   | ✓   |     | ✓   |
+/// | [`Strict`]           | ✓   | ✓   | ✓   | ✓   | ✓   |
+///
+/// The single quote is escaped as `&#39;`, and the others as in
+/// [`Escaper`].
+///
+/// [`DoubleQuotedAttr`] is the default, and is what `html!` uses unless told
+/// otherwise. To use another policy for a splice, put `#[escape(...)]`
+/// before it, with the name of the policy in `snake_case`:
+///
+/// ```rust
+/// use maud::html;
+///
+/// let title = "Maud's \"rocks\"";
+/// let markup = html! {
+///     h1 { #[escape(text)] (title) }
+///     p { #[escape(strict)] (title) }
+/// };
+/// assert_eq!(
+///     markup.into_string(),
+///     r#"<h1>Maud's "rocks"</h1><p>Maud&#39;s &quot;rocks&quot;</p>"#,
+/// );
+/// ```
+///
+/// Since `html!` writes attribute values in double quotes, it rejects
+/// `#[escape(text)]` and `#[escape(single_quoted_attr)]` on a splice in an
+/// attribute value.
+///
+/// [`Text`]: EscapePolicy::Text
+/// [`DoubleQuotedAttr`]: EscapePolicy::DoubleQuotedAttr
+/// [`SingleQuotedAttr`]: EscapePolicy::SingleQuotedAttr
+/// [`Strict`]: EscapePolicy::Strict
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EscapePolicy {
+    /// Escapes what's needed in element content, but not in an attribute
+    /// value.
+    Text,
+    /// Escapes what's needed in element content or a double-quoted attribute
+    /// value.
+    #[default]
+    DoubleQuotedAttr,
+    /// Escapes what's needed in element content or a single-quoted attribute
+    /// value.
+    SingleQuotedAttr,
+    /// Escapes what's needed in element content, or in an attribute value
+    /// with either kind of quote.
+    Strict,
+}
+
+impl EscapePolicy {
+    /// Escapes a string, appending the result to `output`.
+    pub(crate) fn escape_to_string(self, input: &str, output: &mut String) {
+        match self {
+            EscapePolicy::Text => escape::escape_bytes_to_string(input, b"&<>", output),
+            EscapePolicy::DoubleQuotedAttr => escape::escape_to_string(input, output),
+            EscapePolicy::SingleQuotedAttr => {
+                escape::escape_bytes_to_string(input, b"&<>'", output)
+            }
+            EscapePolicy::Strict => escape::escape_bytes_to_string(input, b"&<>\"'", output),
+        }
+    }
+}
+
 /// Represents a type that can be rendered as HTML.
 ///
 /// To implement this for your own type, override either the `.render()`
@@ -92,6 +202,18 @@ impl fmt::Write for Escaper<'_> {
 ///     }
 /// }
 /// ```
+///
+/// The same can be written with `#[derive(Render)]`, which takes a template
+/// in `html!` syntax:
+///
+/// ```rust
+/// use maud::Render;
+///
+/// /// Provides a shorthand for linking to a CSS stylesheet.
+/// #[derive(Render)]
+/// #[maud(template = link rel="stylesheet" type="text/css" href=(_0);)]
+/// pub struct Stylesheet(&'static str);
+/// ```
 pub trait Render {
     /// Renders `self` as a block of `Markup`.
     fn render(&self) -> Markup {
@@ -112,48 +234,124 @@ pub trait Render {
     fn render_to(&self, buffer: &mut String) {
         buffer.push_str(&self.render().into_string());
     }
+
+    /// Appends a representation of `self` to the given buffer, escaped to
+    /// suit the given [`Context`].
+    ///
+    /// The `html!` macro calls this method instead of `.render_to()` when a
+    /// splice appears in a `<script>` or `<style>` element, or in a URL,
+    /// event handler or `style` attribute, or when it has an
+    /// `#[escape(...)]` attribute. See the [`context`] module for details.
+    ///
+    /// Its default implementation just calls `.render_to()`, which treats
+    /// the output as trusted markup. Types that represent plain text, such
+    /// as `str` and `String`, override it to escape their contents.
+    fn render_to_context(&self, context: Context, buffer: &mut String) {
+        let _ = context;
+        self.render_to(buffer);
+    }
+
+    /// Writes a representation of `self` to the given byte stream.
+    ///
+    /// Its default implementation renders to a temporary `String` using
+    /// `.render_to()`, then writes that string in one go.
+    ///
+    /// To write a template straight to a stream, without the temporary
+    /// buffer, use the `html_to!` macro instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use maud::{html, Render};
+    ///
+    /// let mut bytes = Vec::new();
+    /// html! { p { "Hello, world!" } }
+    ///     .render_to_writer(&mut bytes)
+    ///     .unwrap();
+    /// assert_eq!(bytes, b"<p>Hello, world!</p>");
+    /// ```
+    #[cfg(feature = "std")]
+    fn render_to_writer(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let mut buffer = String::new();
+        self.render_to(&mut buffer);
+        writer.write_all(buffer.as_bytes())
+    }
 }
 
 impl Render for str {
     fn render_to(&self, w: &mut String) {
         escape::escape_to_string(self, w);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        context::escape_to_string(self, context, w);
+    }
 }
 
 impl Render for String {
     fn render_to(&self, w: &mut String) {
         str::render_to(self, w);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        str::render_to_context(self, context, w);
+    }
 }
 
 impl Render for Cow<'_, str> {
     fn render_to(&self, w: &mut String) {
         str::render_to(self, w);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        str::render_to_context(self, context, w);
+    }
 }
 
 impl Render for Arguments<'_> {
     fn render_to(&self, w: &mut String) {
         let _ = Escaper::new(w).write_fmt(*self);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        if context == Context::Html {
+            self.render_to(w);
+        } else {
+            let mut s = String::new();
+            let _ = s.write_fmt(*self);
+            s.render_to_context(context, w);
+        }
+    }
 }
 
 impl<T: Render + ?Sized> Render for &T {
     fn render_to(&self, w: &mut String) {
         T::render_to(self, w);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        T::render_to_context(self, context, w);
+    }
 }
 
 impl<T: Render + ?Sized> Render for &mut T {
     fn render_to(&self, w: &mut String) {
         T::render_to(self, w);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        T::render_to_context(self, context, w);
+    }
 }
 
 impl<T: Render + ?Sized> Render for Box<T> {
     fn render_to(&self, w: &mut String) {
         T::render_to(self, w);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        T::render_to_context(self, context, w);
+    }
 }
 
 #[cfg(target_has_atomic = "ptr")]
@@ -161,8 +359,128 @@ impl<T: Render + ?Sized> Render for alloc::sync::Arc<T> {
     fn render_to(&self, w: &mut String) {
         T::render_to(self, w);
     }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        T::render_to_context(self, context, w);
+    }
 }
 
+impl Render for char {
+    fn render_to(&self, w: &mut String) {
+        self.encode_utf8(&mut [0; 4]).render_to(w);
+    }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        self.encode_utf8(&mut [0; 4]).render_to_context(context, w);
+    }
+}
+
+/// Renders `true` or `false`.
+///
+/// When a `bool` is the whole value of an attribute, as in
+/// `disabled=(flag)`, it toggles the attribute instead. This doesn't apply to
+/// `aria-*` and `data-*` attributes, where `true` and `false` are meaningful.
+impl Render for bool {
+    fn render_to(&self, w: &mut String) {
+        w.push_str(if *self { "true" } else { "false" });
+    }
+}
+
+impl<T: Render> Render for Option<T> {
+    fn render_to(&self, w: &mut String) {
+        if let Some(value) = self {
+            value.render_to(w);
+        }
+    }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        if let Some(value) = self {
+            value.render_to_context(context, w);
+        }
+    }
+}
+
+impl<T: Render, E: Render> Render for Result<T, E> {
+    fn render_to(&self, w: &mut String) {
+        match self {
+            Ok(value) => value.render_to(w),
+            Err(error) => error.render_to(w),
+        }
+    }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        match self {
+            Ok(value) => value.render_to_context(context, w),
+            Err(error) => error.render_to_context(context, w),
+        }
+    }
+}
+
+impl<T: Render> Render for [T] {
+    fn render_to(&self, w: &mut String) {
+        for item in self {
+            item.render_to(w);
+        }
+    }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        for item in self {
+            item.render_to_context(context, w);
+        }
+    }
+}
+
+impl<T: Render, const N: usize> Render for [T; N] {
+    fn render_to(&self, w: &mut String) {
+        self.as_slice().render_to(w);
+    }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        self.as_slice().render_to_context(context, w);
+    }
+}
+
+impl<T: Render> Render for Vec<T> {
+    fn render_to(&self, w: &mut String) {
+        self.as_slice().render_to(w);
+    }
+
+    fn render_to_context(&self, context: Context, w: &mut String) {
+        self.as_slice().render_to_context(context, w);
+    }
+}
+
+macro_rules! impl_render_for_tuple {
+    ($($name:ident)*) => {
+        impl<$($name: Render),*> Render for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn render_to(&self, w: &mut String) {
+                let ($($name,)*) = self;
+                $($name.render_to(w);)*
+            }
+
+            #[allow(non_snake_case)]
+            fn render_to_context(&self, context: Context, w: &mut String) {
+                let ($($name,)*) = self;
+                $($name.render_to_context(context, w);)*
+            }
+        }
+    };
+}
+
+impl_render_for_tuple! { A }
+impl_render_for_tuple! { A B }
+impl_render_for_tuple! { A B C }
+impl_render_for_tuple! { A B C D }
+impl_render_for_tuple! { A B C D E }
+impl_render_for_tuple! { A B C D E F }
+impl_render_for_tuple! { A B C D E F G }
+impl_render_for_tuple! { A B C D E F G H }
+impl_render_for_tuple! { A B C D E F G H I }
+impl_render_for_tuple! { A B C D E F G H I J }
+impl_render_for_tuple! { A B C D E F G H I J K }
+impl_render_for_tuple! { A B C D E F G H I J K L }
+
 macro_rules! impl_render_with_display {
     ($($ty:ty)*) => {
         $(
@@ -176,7 +494,7 @@ macro_rules! impl_render_with_display {
 }
 
 impl_render_with_display! {
-    char f32 f64
+    f32 f64
 }
 
 macro_rules! impl_render_with_itoa {
@@ -220,11 +538,76 @@ pub fn display(value: impl Display) -> impl Render {
         fn render_to(&self, w: &mut String) {
             format_args!("{0}", self.0).render_to(w);
         }
+
+        fn render_to_context(&self, context: Context, w: &mut String) {
+            format_args!("{0}", self.0).render_to_context(context, w);
+        }
     }
 
     DisplayWrapper(value)
 }
 
+/// Renders each item of an iterator, with a separator between them.
+///
+/// The iterator is cloned each time the result is rendered, so it should be
+/// cheap to clone; iterators over slices and ranges are fine.
+///
+/// # Example
+///
+/// ```rust
+/// use maud::html;
+///
+/// let ponies = ["Applejack", "Fluttershy", "Pinkie Pie"];
+///
+/// let markup = html! {
+///     p { (maud::join(&ponies, ", ")) }
+///     p { (maud::join(ponies.iter().map(|pony| html! { b { (pony) } }), html! { br; })) }
+/// };
+///
+/// assert_eq!(
+///     markup.into_string(),
+///     concat!(
+///         "<p>Applejack, Fluttershy, Pinkie Pie</p>",
+///         "<p><b>Applejack</b><br><b>Fluttershy</b><br><b>Pinkie Pie</b></p>",
+///     ),
+/// );
+/// ```
+pub fn join<I>(items: I, separator: impl Render) -> impl Render
+where
+    I: IntoIterator,
+    I::IntoIter: Clone,
+    I::Item: Render,
+{
+    struct Join<I, S>(I, S);
+
+    impl<I, S> Render for Join<I, S>
+    where
+        I: Iterator + Clone,
+        I::Item: Render,
+        S: Render,
+    {
+        fn render_to(&self, w: &mut String) {
+            for (i, item) in self.0.clone().enumerate() {
+                if i > 0 {
+                    self.1.render_to(w);
+                }
+                item.render_to(w);
+            }
+        }
+
+        fn render_to_context(&self, context: Context, w: &mut String) {
+            for (i, item) in self.0.clone().enumerate() {
+                if i > 0 {
+                    self.1.render_to_context(context, w);
+                }
+                item.render_to_context(context, w);
+            }
+        }
+    }
+
+    Join(items.into_iter(), separator)
+}
+
 /// A wrapper that renders the inner value without escaping.
 #[derive(Debug, Clone, Copy)]
 pub struct PreEscaped<T>(pub T);
@@ -233,6 +616,11 @@ impl<T: AsRef<str>> Render for PreEscaped<T> {
     fn render_to(&self, w: &mut String) {
         w.push_str(self.0.as_ref());
     }
+
+    #[cfg(feature = "std")]
+    fn render_to_writer(&self, writer: &mut dyn std::io::Write) -> std::io::Result<()> {
+        writer.write_all(self.0.as_ref().as_bytes())
+    }
 }
 
 /// A block of markup is a string that does not need to be escaped.
@@ -247,6 +635,74 @@ impl<T: Into<String>> PreEscaped<T> {
     }
 }
 
+impl<T: AsRef<str>> PreEscaped<T> {
+    /// Returns the markup with block elements indented, one per line.
+    ///
+    /// This is meant for debugging and snapshot tests, where a single long
+    /// line is hard to read and diff. Whitespace is only added between block
+    /// elements like `div` and `li`. The contents of `pre`, `textarea`,
+    /// `script` and `style`, along with anything mixed in with text or inline
+    /// elements like `span`, are kept as they are.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use maud::html;
+    ///
+    /// let markup = html! {
+    ///     ul {
+    ///         li { "Rarity" }
+    ///         li { "Applejack " em { "(and co.)" } }
+    ///     }
+    /// };
+    /// assert_eq!(
+    ///     markup.pretty(),
+    ///     "<ul>\n  <li>Rarity</li>\n  <li>Applejack <em>(and co.)</em></li>\n</ul>",
+    /// );
+    /// ```
+    pub fn pretty(&self) -> String {
+        pretty::pretty(self.0.as_ref())
+    }
+
+    /// Converts the markup into plain text, for places where HTML can't go,
+    /// like the plain text part of an email or a `<meta name="description">`.
+    ///
+    /// Tags are removed and character references like `&amp;` are decoded.
+    /// Runs of whitespace are collapsed into a single space, except in
+    /// `pre`. Block elements start on a new line, with a blank line around
+    /// paragraphs, headings and lists, and list items start with `- ` or
+    /// their number. `br` becomes a line break, and the contents of `head`,
+    /// `script`, `style` and `template` are left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use maud::html;
+    ///
+    /// let markup = html! {
+    ///     h1 { "Rocks & minerals" }
+    ///     p { "Maud's " em { "favorite" } " rocks:" }
+    ///     ul {
+    ///         li { "Boulder" }
+    ///         li { "Granite" }
+    ///     }
+    /// };
+    /// assert_eq!(
+    ///     markup.to_text(),
+    ///     "Rocks & minerals\n\nMaud's favorite rocks:\n\n- Boulder\n- Granite",
+    /// );
+    /// ```
+    pub fn to_text(&self) -> String {
+        text::to_text(self.0.as_ref())
+    }
+}
+
+impl<T: AsRef<str>> AsRef<str> for PreEscaped<T> {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
 impl<T: Into<String>> From<PreEscaped<T>> for String {
     fn from(value: PreEscaped<T>) -> String {
         value.into_string()
@@ -347,6 +803,17 @@ mod actix_support {
                 .unwrap()
         }
     }
+
+    #[cfg(feature = "stream")]
+    impl<F: core::future::Future<Output = ()> + 'static> Responder for crate::stream::MarkupStream<F> {
+        type Body = actix_web_dep::body::BoxBody;
+
+        fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+            HttpResponse::Ok()
+                .content_type(header::ContentType::html())
+                .streaming(crate::stream::TryMarkupStream(self))
+        }
+    }
 }
 
 #[cfg(feature = "tide")]
@@ -394,6 +861,21 @@ mod axum_support {
             (headers, self.0).into_response()
         }
     }
+
+    #[cfg(feature = "stream")]
+    impl<F> IntoResponse for crate::stream::MarkupStream<F>
+    where
+        F: core::future::Future<Output = ()> + Send + 'static,
+    {
+        fn into_response(self) -> Response {
+            let headers = [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/html; charset=utf-8"),
+            )];
+            let body = axum_core::body::Body::from_stream(crate::stream::TryMarkupStream(self));
+            (headers, body).into_response()
+        }
+    }
 }
 
 #[cfg(feature = "warp")]
@@ -407,6 +889,19 @@ mod warp_support {
             reply::html(self.into_string()).into_response()
         }
     }
+
+    #[cfg(feature = "stream")]
+    impl<F> Reply for crate::stream::MarkupStream<F>
+    where
+        F: core::future::Future<Output = ()> + Send + 'static,
+    {
+        fn into_response(self) -> Response {
+            let stream = reply::stream(crate::stream::SyncStream::new(
+                crate::stream::TryMarkupStream(self),
+            ));
+            reply::with_header(stream, "content-type", "text/html; charset=utf-8").into_response()
+        }
+    }
 }
 
 #[cfg(feature = "submillisecond")]
@@ -448,11 +943,54 @@ mod salvo_support {
     }
 }
 
+#[cfg(feature = "uuid")]
+mod uuid_support {
+    use crate::{Render, context::Context};
+    use alloc::string::String;
+    use uuid::Uuid;
+
+    /// Renders a UUID in its usual hyphenated, lowercase form.
+    impl Render for Uuid {
+        fn render_to(&self, w: &mut String) {
+            w.push_str(self.hyphenated().encode_lower(&mut Uuid::encode_buffer()));
+        }
+
+        fn render_to_context(&self, context: Context, w: &mut String) {
+            self.hyphenated()
+                .encode_lower(&mut Uuid::encode_buffer())
+                .render_to_context(context, w);
+        }
+    }
+}
+
+#[cfg(feature = "url")]
+mod url_support {
+    use crate::{Render, context::Context};
+    use alloc::string::String;
+    use url::Url;
+
+    /// Renders a URL as a string.
+    ///
+    /// In a URL attribute like `href`, this goes through the same checks as a
+    /// spliced string, so a `javascript:` URL is still replaced with a
+    /// harmless one.
+    impl Render for Url {
+        fn render_to(&self, w: &mut String) {
+            self.as_str().render_to(w);
+        }
+
+        fn render_to_context(&self, context: Context, w: &mut String) {
+            self.as_str().render_to_context(context, w);
+        }
+    }
+}
+
 #[doc(hidden)]
 pub mod macro_private {
-    use crate::{Render, display};
-    use alloc::string::String;
-    use core::fmt::Display;
+    use crate::{Children, Component, Render, context::Context, display};
+    pub use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::{self, Display};
 
     #[doc(hidden)]
     #[macro_export]
@@ -463,6 +1001,14 @@ pub mod macro_private {
                 x => (&&x).implements_render_or_display().render_to(x.0, $buffer),
             }
         }};
+        ($x:expr, $buffer:expr, $context:expr) => {{
+            use $crate::macro_private::*;
+            match ChooseRenderOrDisplay($x) {
+                x => (&&x)
+                    .implements_render_or_display()
+                    .render_to_context(x.0, $context, $buffer),
+            }
+        }};
     }
 
     pub use render_to;
@@ -487,14 +1033,389 @@ pub mod macro_private {
     impl<T: Display> ViaDisplay for ChooseRenderOrDisplay<T> {}
 
     impl ViaRenderTag {
-        pub fn render_to<T: Render + ?Sized>(self, value: &T, buffer: &mut String) {
-            value.render_to(buffer);
+        pub fn render_to<T: Render + ?Sized, B: Buffer + ?Sized>(self, value: &T, buffer: &mut B) {
+            buffer.with_string(|buffer| value.render_to(buffer));
+        }
+
+        pub fn render_to_context<T: Render + ?Sized, B: Buffer + ?Sized>(
+            self,
+            value: &T,
+            context: Context,
+            buffer: &mut B,
+        ) {
+            buffer.with_string(|buffer| value.render_to_context(context, buffer));
         }
     }
 
     impl ViaDisplayTag {
-        pub fn render_to<T: Display + ?Sized>(self, value: &T, buffer: &mut String) {
-            display(value).render_to(buffer);
+        pub fn render_to<T: Display + ?Sized, B: Buffer + ?Sized>(self, value: &T, buffer: &mut B) {
+            buffer.with_string(|buffer| display(value).render_to(buffer));
+        }
+
+        pub fn render_to_context<T: Display + ?Sized, B: Buffer + ?Sized>(
+            self,
+            value: &T,
+            context: Context,
+            buffer: &mut B,
+        ) {
+            buffer.with_string(|buffer| display(value).render_to_context(context, buffer));
+        }
+    }
+
+    pub struct ChooseToggleOrValue<T>(pub T);
+
+    pub struct ViaToggleTag;
+    pub struct ViaValueTag;
+
+    pub trait ViaToggle {
+        fn toggle_or_value(&self) -> ViaToggleTag {
+            ViaToggleTag
+        }
+    }
+    pub trait ViaValue {
+        fn toggle_or_value(&self) -> ViaValueTag {
+            ViaValueTag
+        }
+    }
+
+    impl ViaToggle for &ChooseToggleOrValue<&bool> {}
+    impl ViaToggle for &ChooseToggleOrValue<&&bool> {}
+    impl<T> ViaValue for ChooseToggleOrValue<T> {}
+
+    pub trait AsBool {
+        fn as_bool(&self) -> bool;
+    }
+
+    impl AsBool for bool {
+        fn as_bool(&self) -> bool {
+            *self
+        }
+    }
+
+    impl AsBool for &bool {
+        fn as_bool(&self) -> bool {
+            **self
+        }
+    }
+
+    impl ViaToggleTag {
+        /// Returns whether the attribute should be written.
+        pub fn toggle<T: AsBool>(self, value: &T) -> Option<bool> {
+            Some(value.as_bool())
+        }
+    }
+
+    impl ViaValueTag {
+        /// Returns `None`, as the value should be written instead.
+        pub fn toggle<T: ?Sized>(self, _value: &T) -> Option<bool> {
+            None
+        }
+    }
+
+    pub fn render_component<C: Component + ?Sized, B: Buffer + ?Sized>(
+        component: &C,
+        children: &Children,
+        buffer: &mut B,
+    ) {
+        buffer.with_string(|buffer| component.render_to(children, buffer));
+    }
+
+    /// Writes the current Content Security Policy nonce as an attribute, if
+    /// there is one.
+    pub fn write_csp_nonce<B: Buffer + ?Sized>(buffer: &mut B) {
+        #[cfg(feature = "csp")]
+        if let Some(nonce) = buffer.csp_nonce() {
+            buffer.with_string(|buffer| crate::csp::write_nonce_attr(&nonce, buffer));
+        }
+        #[cfg(not(feature = "csp"))]
+        let _ = buffer;
+    }
+
+    /// An item in an attribute spread (`..(attrs)`).
+    pub trait SpreadAttr {
+        fn name(&self) -> &str;
+        fn render_value(&self, context: Context, buffer: &mut String);
+    }
+
+    impl<K: AsRef<str>, V: Render> SpreadAttr for (K, V) {
+        fn name(&self) -> &str {
+            self.0.as_ref()
+        }
+
+        fn render_value(&self, context: Context, buffer: &mut String) {
+            self.1.render_to_context(context, buffer);
+        }
+    }
+
+    impl<T: SpreadAttr + ?Sized> SpreadAttr for &T {
+        fn name(&self) -> &str {
+            T::name(self)
+        }
+
+        fn render_value(&self, context: Context, buffer: &mut String) {
+            T::render_value(self, context, buffer);
+        }
+    }
+
+    /// Writes the attribute spreads on an element, skipping any attribute
+    /// with an invalid name or a name that's already been written, whether by
+    /// the element itself or by an earlier spread. This is the same in every
+    /// build profile, so a page never renders differently in production.
+    pub struct Spreads<'a> {
+        static_names: &'a [&'a str],
+        names: Vec<String>,
+    }
+
+    impl<'a> Spreads<'a> {
+        pub fn new(static_names: &'a [&'a str]) -> Self {
+            Spreads {
+                static_names,
+                names: Vec::new(),
+            }
+        }
+
+        pub fn render<I, B>(&mut self, attrs: I, buffer: &mut B)
+        where
+            I: IntoIterator,
+            I::Item: SpreadAttr,
+            B: Buffer + ?Sized,
+        {
+            buffer.with_string(|buffer| {
+                for attr in attrs {
+                    let name = attr.name();
+                    if !is_valid_attribute_name(name) || self.contains(name) {
+                        continue;
+                    }
+                    buffer.push(' ');
+                    buffer.push_str(name);
+                    buffer.push_str("=\"");
+                    attr.render_value(Context::for_attribute(name), buffer);
+                    buffer.push('"');
+                    self.names.push(name.into());
+                }
+            });
+        }
+
+        /// Writes the current Content Security Policy nonce, unless a spread
+        /// has already written a `nonce` attribute.
+        pub fn write_csp_nonce<B: Buffer + ?Sized>(&self, buffer: &mut B) {
+            if !self.contains("nonce") {
+                write_csp_nonce(buffer);
+            }
+        }
+
+        /// Returns whether an attribute with the given name has been written.
+        fn contains(&self, name: &str) -> bool {
+            self.static_names
+                .iter()
+                .copied()
+                .chain(self.names.iter().map(String::as_str))
+                .any(|written| written.eq_ignore_ascii_case(name))
+        }
+    }
+
+    /// Returns whether the given string is a valid attribute name.
+    ///
+    /// HTML allows any characters except whitespace, controls, quotes, `>`,
+    /// `/` and `=`. We also reject `<` and `&`, which are never used in
+    /// practice.
+    fn is_valid_attribute_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.chars().all(|c| {
+                !(c.is_whitespace()
+                    || c.is_control()
+                    || matches!(c, '"' | '\'' | '>' | '/' | '=' | '<' | '&'))
+            })
+    }
+
+    /// A destination for the code generated by `html!` and `html_to!`.
+    ///
+    /// Literal text is written with an inherent `push_str` method, while
+    /// splices are rendered through `with_string`.
+    pub trait Buffer {
+        /// The value that `html_to!` evaluates to.
+        type Output;
+
+        /// Calls `f` with a `String` that the rendered value should be
+        /// appended to.
+        fn with_string(&mut self, f: impl FnOnce(&mut String));
+
+        /// Returns the Content Security Policy nonce to add to `script` and
+        /// `style` elements, which is the current one unless the buffer
+        /// holds its own.
+        #[cfg(feature = "csp")]
+        fn csp_nonce(&self) -> Option<crate::csp::Nonce> {
+            crate::csp::Nonce::current()
+        }
+
+        fn finish(self) -> Self::Output
+        where
+            Self: Sized;
+    }
+
+    impl Buffer for String {
+        type Output = ();
+
+        fn with_string(&mut self, f: impl FnOnce(&mut String)) {
+            f(self);
+        }
+
+        fn finish(self) {}
+    }
+
+    impl<B: Buffer + ?Sized> Buffer for &mut B {
+        type Output = ();
+
+        fn with_string(&mut self, f: impl FnOnce(&mut String)) {
+            B::with_string(self, f);
+        }
+
+        #[cfg(feature = "csp")]
+        fn csp_nonce(&self) -> Option<crate::csp::Nonce> {
+            B::csp_nonce(self)
+        }
+
+        fn finish(self) {}
+    }
+
+    /// Adapts a [`fmt::Write`] for use as a [`Buffer`].
+    ///
+    /// Splices are rendered into a scratch buffer, which is reused between
+    /// splices. The first error returned by the writer is stored, and all
+    /// writes after it are skipped.
+    pub struct FmtBuffer<'a, W: ?Sized> {
+        writer: &'a mut W,
+        scratch: String,
+        result: fmt::Result,
+    }
+
+    impl<W: fmt::Write + ?Sized> FmtBuffer<'_, W> {
+        pub fn push_str(&mut self, string: &str) {
+            if self.result.is_ok() {
+                self.result = self.writer.write_str(string);
+            }
+        }
+    }
+
+    impl<W: fmt::Write + ?Sized> Buffer for FmtBuffer<'_, W> {
+        type Output = fmt::Result;
+
+        fn with_string(&mut self, f: impl FnOnce(&mut String)) {
+            let mut scratch = core::mem::take(&mut self.scratch);
+            f(&mut scratch);
+            self.push_str(&scratch);
+            scratch.clear();
+            self.scratch = scratch;
+        }
+
+        fn finish(self) -> fmt::Result {
+            self.result
+        }
+    }
+
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! buffer {
+        ($buffer:expr) => {{
+            use $crate::macro_private::*;
+            match ChooseBuffer($buffer) {
+                x => (&&&x).implements_string_or_write().buffer(x.0),
+            }
+        }};
+    }
+
+    pub use buffer;
+
+    pub struct ChooseBuffer<T>(pub T);
+
+    pub struct ViaStringTag;
+    pub struct ViaFmtWriteTag;
+    #[cfg(feature = "std")]
+    pub struct ViaIoWriteTag;
+
+    pub trait ViaString {
+        fn implements_string_or_write(&self) -> ViaStringTag {
+            ViaStringTag
+        }
+    }
+    pub trait ViaFmtWrite {
+        fn implements_string_or_write(&self) -> ViaFmtWriteTag {
+            ViaFmtWriteTag
+        }
+    }
+    #[cfg(feature = "std")]
+    pub trait ViaIoWrite {
+        fn implements_string_or_write(&self) -> ViaIoWriteTag {
+            ViaIoWriteTag
+        }
+    }
+
+    impl ViaString for &&ChooseBuffer<&mut String> {}
+    impl<W: fmt::Write + ?Sized> ViaFmtWrite for &ChooseBuffer<&mut W> {}
+    #[cfg(feature = "std")]
+    impl<W: std::io::Write + ?Sized> ViaIoWrite for ChooseBuffer<&mut W> {}
+
+    impl ViaStringTag {
+        pub fn buffer(self, buffer: &mut String) -> &mut String {
+            buffer
+        }
+    }
+
+    impl ViaFmtWriteTag {
+        pub fn buffer<W: fmt::Write + ?Sized>(self, writer: &mut W) -> FmtBuffer<'_, W> {
+            FmtBuffer {
+                writer,
+                scratch: String::new(),
+                result: Ok(()),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl ViaIoWriteTag {
+        pub fn buffer<W: std::io::Write + ?Sized>(self, writer: &mut W) -> IoBuffer<'_, W> {
+            IoBuffer {
+                writer,
+                scratch: String::new(),
+                result: Ok(()),
+            }
+        }
+    }
+
+    /// Adapts a [`std::io::Write`] for use as a [`Buffer`].
+    ///
+    /// This works the same way as [`FmtBuffer`], except that the output is
+    /// written as UTF-8 bytes.
+    #[cfg(feature = "std")]
+    pub struct IoBuffer<'a, W: ?Sized> {
+        writer: &'a mut W,
+        scratch: String,
+        result: std::io::Result<()>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<W: std::io::Write + ?Sized> IoBuffer<'_, W> {
+        pub fn push_str(&mut self, string: &str) {
+            if self.result.is_ok() {
+                self.result = self.writer.write_all(string.as_bytes());
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<W: std::io::Write + ?Sized> Buffer for IoBuffer<'_, W> {
+        type Output = std::io::Result<()>;
+
+        fn with_string(&mut self, f: impl FnOnce(&mut String)) {
+            let mut scratch = core::mem::take(&mut self.scratch);
+            f(&mut scratch);
+            self.push_str(&scratch);
+            scratch.clear();
+            self.scratch = scratch;
+        }
+
+        fn finish(self) -> std::io::Result<()> {
+            self.result
         }
     }
 }
@@ -0,0 +1,378 @@
+//! Indenting rendered markup, for debugging and snapshot tests.
+//!
+//! The markup is split into tags and text, then built into a tree. Elements
+//! that contain only other block-level elements put each child on its own
+//! line. Everything else is written back exactly as it was, so whitespace is
+//! only ever added where a browser would ignore it.
+//!
+//! The tree is also used by [`to_text`](crate::text), which walks it to pull
+//! out the text.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::escape::INLINE_ELEMENTS;
+
+/// A piece of markup, borrowed from the input.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// An opening tag, like `<p class="x">`.
+    Start {
+        name: String,
+        raw: &'a str,
+    },
+    /// A closing tag, like `</p>`.
+    End {
+        name: String,
+        raw: &'a str,
+    },
+    /// A comment or doctype.
+    Other(&'a str),
+    Text(&'a str),
+}
+
+pub(crate) fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let (token, len) = next_token(rest);
+        // The contents of these elements aren't markup
+        if let Token::Start { name, .. } = &token
+            && RAW_TEXT_ELEMENTS.contains(&name.as_str())
+        {
+            let closing = find_closing_tag(&rest[len..], name).map_or(rest.len(), |i| len + i);
+            let text = &rest[len..closing];
+            tokens.push(token);
+            if !text.is_empty() {
+                tokens.push(Token::Text(text));
+            }
+            rest = &rest[closing..];
+        } else {
+            tokens.push(token);
+            rest = &rest[len..];
+        }
+    }
+    tokens
+}
+
+/// Splits the next token off the input, returning it with its length in
+/// bytes.
+fn next_token(input: &str) -> (Token<'_>, usize) {
+    let bytes = input.as_bytes();
+    if bytes[0] == b'<' {
+        if input.starts_with("<!--") {
+            let len = input.find("-->").map_or(input.len(), |i| i + 3);
+            return (Token::Other(&input[..len]), len);
+        }
+        if input.starts_with("<!") {
+            let len = input.find('>').map_or(input.len(), |i| i + 1);
+            return (Token::Other(&input[..len]), len);
+        }
+        let (is_end, name_start) = if input.starts_with("</") {
+            (true, 2)
+        } else {
+            (false, 1)
+        };
+        if bytes.get(name_start).is_some_and(u8::is_ascii_alphabetic) {
+            let len = tag_len(input);
+            let raw = &input[..len];
+            let name = raw[name_start..]
+                .split(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            let token = if is_end {
+                Token::End { name, raw }
+            } else {
+                Token::Start { name, raw }
+            };
+            return (token, len);
+        }
+    }
+    // Text runs up to the next thing that looks like a tag
+    let skip = usize::from(bytes[0] == b'<');
+    let len = input[skip..].find('<').map_or(input.len(), |i| i + skip);
+    (Token::Text(&input[..len]), len)
+}
+
+/// Returns the length of the tag at the start of the input, skipping over
+/// any `>` in quoted attribute values.
+fn tag_len(input: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+/// Finds the `</name` that ends a raw text element, ignoring case.
+fn find_closing_tag(input: &str, name: &str) -> Option<usize> {
+    let bytes = input.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        bytes[i..].starts_with(b"</")
+            && bytes[i + 2..]
+                .get(..name.len())
+                .is_some_and(|candidate| candidate.eq_ignore_ascii_case(name.as_bytes()))
+    })
+}
+
+pub(crate) enum Node<'a> {
+    Element {
+        name: String,
+        start: &'a str,
+        children: Vec<Node<'a>>,
+        end: Option<&'a str>,
+    },
+    Other(&'a str),
+    Text(&'a str),
+}
+
+impl Node<'_> {
+    pub(crate) fn is_block(&self) -> bool {
+        match self {
+            Node::Element { name, .. } => !INLINE_ELEMENTS.contains(&name.as_str()),
+            Node::Other(_) => true,
+            Node::Text(_) => false,
+        }
+    }
+
+    fn is_whitespace(&self) -> bool {
+        matches!(self, Node::Text(text) if text.trim_ascii().is_empty())
+    }
+}
+
+/// Builds a tree out of tokens. End tags without a matching start tag are kept
+/// as text, while unclosed elements end with their parent.
+pub(crate) fn parse<'a>(tokens: Vec<Token<'a>>) -> Vec<Node<'a>> {
+    // Each open element, with the children seen so far
+    let mut stack: Vec<(String, &'a str, Vec<Node<'a>>)> = Vec::new();
+    let mut top = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Start { name, raw } => {
+                if VOID_ELEMENTS.contains(&name.as_str()) || raw.ends_with("/>") {
+                    let node = Node::Element {
+                        name,
+                        start: raw,
+                        children: Vec::new(),
+                        end: None,
+                    };
+                    push(&mut stack, &mut top, node);
+                } else {
+                    stack.push((name, raw, Vec::new()));
+                }
+            }
+            Token::End { name, raw } => {
+                if stack.iter().any(|(open, _, _)| *open == name) {
+                    loop {
+                        let (open, start, children) = stack.pop().unwrap();
+                        let matched = open == name;
+                        let node = Node::Element {
+                            name: open,
+                            start,
+                            children,
+                            end: if matched { Some(raw) } else { None },
+                        };
+                        push(&mut stack, &mut top, node);
+                        if matched {
+                            break;
+                        }
+                    }
+                } else {
+                    push(&mut stack, &mut top, Node::Text(raw));
+                }
+            }
+            Token::Other(raw) => push(&mut stack, &mut top, Node::Other(raw)),
+            Token::Text(text) => push(&mut stack, &mut top, Node::Text(text)),
+        }
+    }
+
+    while let Some((name, start, children)) = stack.pop() {
+        let node = Node::Element {
+            name,
+            start,
+            children,
+            end: None,
+        };
+        push(&mut stack, &mut top, node);
+    }
+    top
+}
+
+fn push<'a>(
+    stack: &mut [(String, &'a str, Vec<Node<'a>>)],
+    top: &mut Vec<Node<'a>>,
+    node: Node<'a>,
+) {
+    match stack.last_mut() {
+        Some((_, _, children)) => children.push(node),
+        None => top.push(node),
+    }
+}
+
+/// Returns whether a list of nodes should be laid out one per line.
+fn is_block_content(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| !node.is_whitespace())
+        && nodes
+            .iter()
+            .all(|node| node.is_block() || node.is_whitespace())
+}
+
+fn write_block(nodes: &[Node], depth: usize, output: &mut String) {
+    for node in nodes.iter().filter(|node| !node.is_whitespace()) {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        for _ in 0..depth {
+            output.push_str("  ");
+        }
+        match node {
+            Node::Element {
+                name,
+                start,
+                children,
+                end,
+            } if !PRESERVE_ELEMENTS.contains(&name.as_str()) && is_block_content(children) => {
+                output.push_str(start);
+                write_block(children, depth + 1, output);
+                if let Some(end) = end {
+                    output.push('\n');
+                    for _ in 0..depth {
+                        output.push_str("  ");
+                    }
+                    output.push_str(end);
+                }
+            }
+            _ => write_inline(node, output),
+        }
+    }
+}
+
+fn write_inline(node: &Node, output: &mut String) {
+    match node {
+        Node::Element {
+            start,
+            children,
+            end,
+            ..
+        } => {
+            output.push_str(start);
+            for child in children {
+                write_inline(child, output);
+            }
+            if let Some(end) = end {
+                output.push_str(end);
+            }
+        }
+        Node::Other(raw) | Node::Text(raw) => output.push_str(raw),
+    }
+}
+
+/// Indents the given markup. See the [module docs](self) for details.
+pub fn pretty(input: &str) -> String {
+    let nodes = parse(tokenize(input));
+    let mut output = String::with_capacity(input.len() * 2);
+    if is_block_content(&nodes) {
+        write_block(&nodes, 0, &mut output);
+    } else {
+        for node in &nodes {
+            write_inline(node, &mut output);
+        }
+    }
+    output
+}
+
+/// Elements whose contents are text, not markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Elements whose contents are written exactly as they are.
+const PRESERVE_ELEMENTS: &[&str] = &["pre", "script", "style", "textarea", "title"];
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+#[cfg(test)]
+mod test {
+    use super::{Token, pretty, tokenize};
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            tokenize(r#"<!DOCTYPE html><a title="a > b">x < y</a><!-- <p> -->"#),
+            [
+                Token::Other("<!DOCTYPE html>"),
+                Token::Start {
+                    name: "a".into(),
+                    raw: r#"<a title="a > b">"#,
+                },
+                Token::Text("x "),
+                Token::Text("< y"),
+                Token::End {
+                    name: "a".into(),
+                    raw: "</a>",
+                },
+                Token::Other("<!-- <p> -->"),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_text() {
+        assert_eq!(
+            tokenize("<script>if (a<b) {}</script><STYLE>p{}</Style>"),
+            [
+                Token::Start {
+                    name: "script".into(),
+                    raw: "<script>",
+                },
+                Token::Text("if (a<b) {}"),
+                Token::End {
+                    name: "script".into(),
+                    raw: "</script>",
+                },
+                Token::Start {
+                    name: "style".into(),
+                    raw: "<STYLE>",
+                },
+                Token::Text("p{}"),
+                Token::End {
+                    name: "style".into(),
+                    raw: "</Style>",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        assert_eq!(
+            pretty("<div><p>Hello <b>world</b></p><hr><ul><li>a</li><li><p>b</p></li></ul></div>"),
+            "<div>\n  <p>Hello <b>world</b></p>\n  <hr>\n  <ul>\n    <li>a</li>\n    <li>\n      <p>b</p>\n    </li>\n  </ul>\n</div>"
+        );
+    }
+
+    #[test]
+    fn preserved() {
+        assert_eq!(
+            pretty("<main><pre><div>a\n  b</div></pre><div><span><div>c</div></span></div></main>"),
+            "<main>\n  <pre><div>a\n  b</div></pre>\n  <div><span><div>c</div></span></div>\n</main>"
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(pretty("<div><p>a</div>"), "<div>\n  <p>a\n</div>");
+        assert_eq!(pretty("<div><p>a</div></span>"), "<div><p>a</div></span>");
+        assert_eq!(pretty("<p>café</p><p>☕</p>"), "<p>café</p>\n<p>☕</p>");
+        assert_eq!(pretty("<div><p>"), "<div>\n  <p>");
+        assert_eq!(pretty("a < b"), "a < b");
+        assert_eq!(pretty(""), "");
+    }
+}
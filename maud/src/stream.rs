@@ -0,0 +1,207 @@
+//! Render markup as an asynchronous stream of chunks.
+//!
+//! The [`html_stream!`](crate::html_stream) macro accepts the same syntax as
+//! `html!`, but expands to a [`MarkupStream`] instead of a `Markup`. The
+//! template runs inside an `async` block, so splices and control structures
+//! may `.await` on other futures. Everything rendered before an `.await` is
+//! sent as a chunk of its own, so a page can send its `<head>` while the rest
+//! of it is still loading.
+//!
+//! Like an `async move` block, the template takes ownership of any variables
+//! that it uses.
+//!
+//! # Example
+//!
+//! ```rust
+//! use futures_util::StreamExt;
+//! use maud::html_stream;
+//!
+//! async fn fetch_comments() -> Vec<&'static str> {
+//!     vec!["First!", "Great post"]
+//! }
+//!
+//! # futures_executor::block_on(async {
+//! let title = "My blog post";
+//! let chunks: Vec<_> = html_stream! {
+//!     h1 { (title) }
+//!     @for comment in fetch_comments().await {
+//!         p { (comment) }
+//!     }
+//! }
+//! .collect()
+//! .await;
+//! assert_eq!(chunks, ["<h1>My blog post</h1>", "<p>First!</p><p>Great post</p>"]);
+//! # });
+//! ```
+
+use alloc::{boxed::Box, string::String, sync::Arc};
+use bytes::Bytes;
+use core::{
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_core::Stream;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A stream of markup, produced by the [`html_stream!`](crate::html_stream)
+/// macro.
+///
+/// Each item is a chunk of UTF-8 encoded HTML.
+#[must_use = "streams do nothing unless polled"]
+pub struct MarkupStream<F> {
+    future: Option<Pin<Box<F>>>,
+    buffer: Arc<Mutex<String>>,
+}
+
+impl<F: Future<Output = ()>> MarkupStream<F> {
+    #[doc(hidden)]
+    pub fn new(template: impl FnOnce(StreamBuffer) -> F) -> MarkupStream<F> {
+        let buffer = Arc::new(Mutex::new(String::new()));
+        let future = template(StreamBuffer {
+            buffer: Arc::clone(&buffer),
+            flushed: false,
+            #[cfg(feature = "csp")]
+            nonce: crate::csp::Nonce::current(),
+        });
+        MarkupStream {
+            future: Some(Box::pin(future)),
+            buffer,
+        }
+    }
+
+    fn take_chunk(&self) -> Option<Bytes> {
+        let chunk = mem::take(&mut *lock(&self.buffer));
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(Bytes::from(chunk))
+        }
+    }
+}
+
+impl<F: Future<Output = ()>> Stream for MarkupStream<F> {
+    type Item = Bytes;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Bytes>> {
+        let Some(future) = &mut self.future else {
+            return Poll::Ready(None);
+        };
+        match future.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                self.future = None;
+                Poll::Ready(self.take_chunk())
+            }
+            Poll::Pending => match self.take_chunk() {
+                Some(chunk) => Poll::Ready(Some(chunk)),
+                None => Poll::Pending,
+            },
+        }
+    }
+}
+
+/// The buffer that the code generated by `html_stream!` writes to.
+#[doc(hidden)]
+pub struct StreamBuffer {
+    buffer: Arc<Mutex<String>>,
+    flushed: bool,
+    /// The nonce that was current when the stream was made, since the
+    /// template may be polled on another thread.
+    #[cfg(feature = "csp")]
+    nonce: Option<crate::csp::Nonce>,
+}
+
+impl StreamBuffer {
+    pub fn push_str(&mut self, string: &str) {
+        lock(&self.buffer).push_str(string);
+    }
+
+    /// Hands everything written so far to the stream.
+    pub fn flush(&mut self) -> Flush<'_> {
+        Flush(self)
+    }
+}
+
+impl crate::macro_private::Buffer for StreamBuffer {
+    type Output = ();
+
+    fn with_string(&mut self, f: impl FnOnce(&mut String)) {
+        f(&mut lock(&self.buffer));
+    }
+
+    #[cfg(feature = "csp")]
+    fn csp_nonce(&self) -> Option<crate::csp::Nonce> {
+        self.nonce.clone()
+    }
+
+    fn finish(self) {}
+}
+
+/// A future that yields once, so that the stream can send the pending chunk.
+#[doc(hidden)]
+pub struct Flush<'a>(&'a mut StreamBuffer);
+
+impl Future for Flush<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let buffer = &mut *self.0;
+        if buffer.flushed || lock(&buffer.buffer).is_empty() {
+            buffer.flushed = false;
+            Poll::Ready(())
+        } else {
+            buffer.flushed = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+fn lock(buffer: &Mutex<String>) -> MutexGuard<'_, String> {
+    buffer.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Adapts a [`MarkupStream`] into a stream of `Result`s, as expected by most
+/// web frameworks.
+#[cfg(any(feature = "axum", feature = "actix-web", feature = "warp"))]
+pub(crate) struct TryMarkupStream<F>(pub(crate) MarkupStream<F>);
+
+#[cfg(any(feature = "axum", feature = "actix-web", feature = "warp"))]
+impl<F: Future<Output = ()>> Stream for TryMarkupStream<F> {
+    type Item = Result<Bytes, core::convert::Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_next(cx)
+            .map(|chunk| chunk.map(Ok))
+    }
+}
+
+/// Makes a stream `Sync`, as required by `warp`.
+#[cfg(feature = "warp")]
+pub(crate) struct SyncStream<S>(S);
+
+#[cfg(feature = "warp")]
+impl<S> SyncStream<S> {
+    pub(crate) fn new(stream: S) -> SyncStream<S> {
+        SyncStream(stream)
+    }
+}
+
+// SAFETY: `Sync` only allows `&SyncStream<S>` to be shared between threads.
+// The field is private and no method takes `&self`, so nothing can reach the
+// inner stream through a shared reference; the only way in is `poll_next`,
+// which needs `Pin<&mut Self>` and so exclusive access. Moving the stream to
+// another thread is covered by `S: Send`.
+#[cfg(feature = "warp")]
+unsafe impl<S: Send> Sync for SyncStream<S> {}
+
+#[cfg(feature = "warp")]
+impl<S: Stream + Unpin> Stream for SyncStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+}
@@ -0,0 +1,255 @@
+//! Converting rendered markup into plain text, for email bodies, snippets and
+//! search indexes.
+//!
+//! The markup is built into a tree, as in [`pretty`](crate::pretty), then
+//! written out as it might look in a browser with styles turned off: tags are
+//! removed, character references are decoded, whitespace is collapsed, block
+//! elements start on a new line and list items get bullets.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{
+    escape,
+    pretty::{self, Node},
+};
+
+/// Converts the given markup into plain text. See the [module docs](self)
+/// for details.
+pub fn to_text(input: &str) -> String {
+    let nodes = pretty::parse(pretty::tokenize(input));
+    let mut writer = Writer {
+        output: String::with_capacity(input.len()),
+        pending_break: Break::None,
+        pending_space: false,
+        bullet: None,
+        lists: Vec::new(),
+    };
+    writer.nodes(&nodes, false);
+    let len = writer.output.trim_end().len();
+    writer.output.truncate(len);
+    writer.output
+}
+
+/// The line break to write before the next piece of text. A larger break
+/// takes the place of a smaller one.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Break {
+    None,
+    Line,
+    Paragraph,
+}
+
+struct Writer {
+    output: String,
+    pending_break: Break,
+    /// Whether there was whitespace since the last piece of text.
+    pending_space: bool,
+    /// The bullet to write before the next piece of text, at the start of a
+    /// list item.
+    bullet: Option<String>,
+    /// The number of the next item in each list that we're in, or `None` for
+    /// an unordered list.
+    lists: Vec<Option<usize>>,
+}
+
+impl Writer {
+    fn nodes(&mut self, nodes: &[Node], preserve: bool) {
+        for node in nodes {
+            match node {
+                Node::Element { name, children, .. } => {
+                    self.element(name, children, node.is_block(), preserve)
+                }
+                Node::Text(text) => {
+                    let text = escape::decode_entities(text);
+                    if preserve {
+                        self.preserved_text(&text);
+                    } else {
+                        self.text(&text);
+                    }
+                }
+                Node::Other(_) => {}
+            }
+        }
+    }
+
+    fn element(&mut self, name: &str, children: &[Node], is_block: bool, preserve: bool) {
+        if SKIPPED_ELEMENTS.contains(&name) {
+            return;
+        }
+        if name == "br" {
+            self.output.push('\n');
+            self.pending_space = false;
+            return;
+        }
+        if !is_block {
+            self.nodes(children, preserve);
+            return;
+        }
+
+        let in_list = !self.lists.is_empty();
+        let block_break = match name {
+            // A list inside a list item is part of that item
+            "ol" | "ul" if in_list => Break::Line,
+            _ if PARAGRAPH_ELEMENTS.contains(&name) => Break::Paragraph,
+            _ => Break::Line,
+        };
+        self.line_break(block_break);
+        match name {
+            "ol" => self.lists.push(Some(1)),
+            "ul" => self.lists.push(None),
+            "li" => {
+                let mut bullet = "  ".repeat(self.lists.len().saturating_sub(1));
+                match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let _ = write!(bullet, "{number}. ");
+                        *number += 1;
+                    }
+                    _ => bullet.push_str("- "),
+                }
+                self.bullet = Some(bullet);
+            }
+            _ => {}
+        }
+
+        self.nodes(children, preserve || name == "pre");
+
+        if matches!(name, "ol" | "ul") {
+            self.lists.pop();
+        }
+        self.line_break(block_break);
+    }
+
+    fn line_break(&mut self, line_break: Break) {
+        self.pending_break = self.pending_break.max(line_break);
+        self.pending_space = false;
+    }
+
+    /// Writes text, with runs of whitespace collapsed into a single space.
+    fn text(&mut self, text: &str) {
+        let mut words = text.split_ascii_whitespace().peekable();
+        if text.starts_with(|c: char| c.is_ascii_whitespace()) {
+            self.pending_space = true;
+        }
+        while let Some(word) = words.next() {
+            self.start_text();
+            if self.pending_space && !self.output.is_empty() && !self.output.ends_with('\n') {
+                self.output.push(' ');
+            }
+            self.output.push_str(word);
+            self.pending_space = words.peek().is_some();
+        }
+        if text.ends_with(|c: char| c.is_ascii_whitespace()) {
+            self.pending_space = true;
+        }
+    }
+
+    /// Writes text exactly as it is, as in a `pre` element.
+    fn preserved_text(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.start_text();
+            if self.pending_space {
+                self.output.push(' ');
+                self.pending_space = false;
+            }
+            self.output.push_str(text);
+        }
+    }
+
+    /// Writes any line break and bullet that come before the next piece of
+    /// text.
+    fn start_text(&mut self) {
+        if !self.output.is_empty() {
+            match self.pending_break {
+                Break::None => {}
+                Break::Line => self.output.push('\n'),
+                Break::Paragraph => self.output.push_str("\n\n"),
+            }
+        }
+        if self.pending_break != Break::None {
+            self.pending_space = false;
+        }
+        self.pending_break = Break::None;
+        if let Some(bullet) = self.bullet.take() {
+            self.output.push_str(&bullet);
+            self.pending_space = false;
+        }
+    }
+}
+
+/// Elements whose contents aren't shown on the page.
+const SKIPPED_ELEMENTS: &[&str] = &["head", "script", "style", "template"];
+
+/// Block elements that are set apart from their surroundings by a blank line.
+const PARAGRAPH_ELEMENTS: &[&str] = &[
+    "blockquote",
+    "dl",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ol",
+    "p",
+    "pre",
+    "table",
+    "ul",
+];
+
+#[cfg(test)]
+mod test {
+    use super::to_text;
+
+    #[test]
+    fn entities() {
+        assert_eq!(
+            to_text("&lt;a&gt; &amp;amp; &quot;&#39;&#x27;&apos; &copy;"),
+            "<a> &amp; \"''' ©"
+        );
+        assert_eq!(
+            to_text("AT&T &bogus; &#0; &#xd800; &#xffffff; &"),
+            "AT&T &bogus; &#0; &#xd800; &#xffffff; &"
+        );
+    }
+
+    #[test]
+    fn whitespace() {
+        assert_eq!(to_text("  Hello,\n  <b> world </b>!  "), "Hello, world !");
+        assert_eq!(to_text("<span>a</span><span>b</span>"), "ab");
+        assert_eq!(to_text("a<br>b<br><br>c"), "a\nb\n\nc");
+    }
+
+    #[test]
+    fn blocks() {
+        assert_eq!(
+            to_text("<h1>Title</h1><p>One</p><p>Two</p><div>Three</div><div>Four</div>"),
+            "Title\n\nOne\n\nTwo\n\nThree\nFour"
+        );
+        assert_eq!(to_text("<div> <div> a </div> </div>"), "a");
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            to_text("<p>Ponies:</p><ul><li>Maud</li><li>Pinkie <ul><li>Pie</li></ul></li></ul>"),
+            "Ponies:\n\n- Maud\n- Pinkie\n  - Pie"
+        );
+        assert_eq!(
+            to_text("<ol><li>a</li><li>b<ol><li>c</li></ol></li></ol>"),
+            "1. a\n2. b\n  1. c"
+        );
+    }
+
+    #[test]
+    fn skipped_and_preserved() {
+        assert_eq!(
+            to_text("<head><title>x</title></head><script>a < b</script><p>Hi<!-- there --></p>"),
+            "Hi"
+        );
+        assert_eq!(
+            to_text("<p>Code:</p><pre>fn main() {\n    &lt;3\n}</pre>"),
+            "Code:\n\nfn main() {\n    <3\n}"
+        );
+    }
+}
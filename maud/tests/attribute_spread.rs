@@ -0,0 +1,97 @@
+use maud::html;
+use std::collections::BTreeMap;
+
+#[test]
+fn pairs() {
+    let attrs = [("aria-label", "Close"), ("data-id", "42")];
+    let result = html! { button ..(attrs) { "×" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<button aria-label="Close" data-id="42">×</button>"#
+    );
+}
+
+#[test]
+fn with_other_attributes() {
+    let attrs = vec![("title".to_string(), "Hi".to_string())];
+    let result = html! { a.link #home href="/" ..(&attrs) { "Home" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<a class="link" id="home" href="/" title="Hi">Home</a>"#
+    );
+    // Borrowed, so still usable here
+    assert_eq!(attrs.len(), 1);
+}
+
+#[test]
+fn map() {
+    let mut attrs = BTreeMap::new();
+    attrs.insert("data-count", 3);
+    attrs.insert("data-total", 10);
+    let result = html! { div ..(&attrs) {} };
+    assert_eq!(
+        result.into_string(),
+        r#"<div data-count="3" data-total="10"></div>"#
+    );
+}
+
+#[test]
+fn implicit_div() {
+    let result = html! { ..([("role", "alert")]) { "!" } };
+    assert_eq!(result.into_string(), r#"<div role="alert">!</div>"#);
+}
+
+#[test]
+fn multiple_spreads() {
+    let result = html! { input ..([("type", "text")]) ..([("name", "q")]); };
+    assert_eq!(result.into_string(), r#"<input type="text" name="q">"#);
+}
+
+#[test]
+fn values_are_escaped() {
+    let attrs = [("title", r#""><script>alert(1)</script>"#)];
+    let result = html! { p ..(attrs) {} };
+    assert_eq!(
+        result.into_string(),
+        r#"<p title="&quot;&gt;&lt;script&gt;alert(1)&lt;/script&gt;"></p>"#
+    );
+}
+
+#[test]
+fn invalid_names_are_skipped() {
+    let attrs = [
+        ("onclick=alert(1) x", "1"),
+        ("", "2"),
+        ("a\"b", "3"),
+        ("data-ok", "4"),
+    ];
+    let result = html! { p ..(attrs) {} };
+    assert_eq!(result.into_string(), r#"<p data-ok="4"></p>"#);
+}
+
+#[test]
+fn duplicates_of_static_attributes_are_skipped() {
+    let attrs = [("CLASS", "evil"), ("data-ok", "1")];
+    let result = html! { p.safe ..(attrs) {} };
+    assert_eq!(result.into_string(), r#"<p class="safe" data-ok="1"></p>"#);
+}
+
+#[test]
+fn duplicates_within_spreads_are_skipped() {
+    let result = html! { p ..([("id", "a")]) ..([("id", "b")]) {} };
+    assert_eq!(result.into_string(), r#"<p id="a"></p>"#);
+}
+
+#[test]
+fn context() {
+    let attrs = [
+        ("href", "javascript:alert(1)"),
+        ("onclick", "x\""),
+        ("style", "color: red; }"),
+    ];
+    let result = html! { a ..(attrs) {} };
+    assert_eq!(
+        result.into_string(),
+        r#"<a href="about:invalid#maud-unsafe-url" onclick="'x\u0022'" style="color: red; \7d "></a>"#
+    );
+}
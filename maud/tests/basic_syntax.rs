@@ -112,6 +112,64 @@ fn empty_attributes_question_mark() {
     assert_eq!(result.into_string(), "<input checked disabled>");
 }
 
+#[test]
+fn bool_attribute_values() {
+    let flags = [true, false];
+    let result = html! {
+        @for flag in &flags {
+            input disabled=(flag);
+        }
+        input checked=(flags[0] && flags[1]) readonly=(!flags[1]);
+    };
+    assert_eq!(
+        result.into_string(),
+        "<input disabled><input><input readonly>"
+    );
+}
+
+#[test]
+fn bool_attribute_values_aria_and_data() {
+    let expanded = false;
+    let result = html! {
+        #[allow(bool_attribute)]
+        button aria-expanded=(expanded) data-open=(expanded) draggable=(true) {}
+    };
+    assert_eq!(
+        result.into_string(),
+        r#"<button aria-expanded="false" data-open="false" draggable="true"></button>"#
+    );
+}
+
+#[test]
+fn bool_attribute_values_other_attributes() {
+    let result = html! {
+        #[allow(bool_attribute)]
+        p title=(true) translate=(false) hx-boost=(true) writingsuggestions=(false) {}
+    };
+    assert_eq!(
+        result.into_string(),
+        r#"<p title="true" translate="false" hx-boost="true" writingsuggestions="false"></p>"#
+    );
+}
+
+#[test]
+fn non_bool_attribute_values() {
+    let result = html! { #[allow(bool_attribute)] input title=("<hi>") size=(3) value=(true); };
+    assert_eq!(
+        result.into_string(),
+        r#"<input title="&lt;hi&gt;" size="3" value="true">"#
+    );
+}
+
+#[test]
+fn bool_text() {
+    let result = html! { p { (true) " " (1 > 2) } script { "let x = " (true) ";" } };
+    assert_eq!(
+        result.into_string(),
+        "<p>true false</p><script>let x = true;</script>"
+    );
+}
+
 #[test]
 fn optional_attribute_some() {
     let result = html! { input value=[Some("value")]; };
@@ -373,3 +431,38 @@ fn div_shorthand_id_with_attrs() {
         r#"<div class="awesome-class" id="unique-id" contenteditable dir="rtl"></div>"#
     );
 }
+
+#[test]
+fn class_attribute_with_shorthand() {
+    let result = html! { p.foo class="bar" { "Hi" } };
+    assert_eq!(result.into_string(), r#"<p class="foo bar">Hi</p>"#);
+}
+
+#[test]
+fn class_attribute_splice_with_shorthand() {
+    let extra = "baz";
+    let visible = false;
+    let result = html! { p class={ "bar " (extra) } .foo .hidden[!visible] {} };
+    assert_eq!(
+        result.into_string(),
+        r#"<p class="bar baz foo hidden"></p>"#
+    );
+}
+
+#[test]
+fn class_attribute_without_shorthand() {
+    let result = html! { p title="a" class="bar" {} };
+    assert_eq!(result.into_string(), r#"<p title="a" class="bar"></p>"#);
+}
+
+#[test]
+fn allow_lints() {
+    let result = html! {
+        #[allow(missing_alt)] img src="spacer.gif";
+        #[allow(missing_href, invalid_nesting)] #main.x { a { "Top" } }
+    };
+    assert_eq!(
+        result.into_string(),
+        r#"<img src="spacer.gif"><div class="x" id="main"><a>Top</a></div>"#
+    );
+}
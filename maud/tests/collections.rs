@@ -0,0 +1,80 @@
+use maud::{PreEscaped, html};
+
+#[test]
+fn option() {
+    let some = Some("<b>");
+    let none: Option<&str> = None;
+    let result = html! { p { (some) } p { (none) } };
+    assert_eq!(result.into_string(), "<p>&lt;b&gt;</p><p></p>");
+}
+
+#[test]
+fn option_in_attribute() {
+    let title: Option<String> = Some("Hi".into());
+    let result = html! { p title=(title) {} };
+    assert_eq!(result.into_string(), r#"<p title="Hi"></p>"#);
+}
+
+#[test]
+fn result() {
+    let ok: Result<u32, &str> = Ok(42);
+    let err: Result<u32, &str> = Err("not found");
+    let result = html! { (ok) " " (err) };
+    assert_eq!(result.into_string(), "42 not found");
+}
+
+#[test]
+fn slices_and_vecs() {
+    let array = [1, 2, 3];
+    let vec = vec![html! { li { "a" } }, html! { li { "b" } }];
+    let result = html! { (array) ul { (vec) } (&array[1..]) };
+    assert_eq!(result.into_string(), "123<ul><li>a</li><li>b</li></ul>23");
+}
+
+#[test]
+fn tuples() {
+    let result = html! { (("a", 1, PreEscaped("<br>"), 'c')) };
+    assert_eq!(result.into_string(), "a1<br>c");
+}
+
+#[test]
+fn nested() {
+    let rows = vec![Some(("x", 1)), None, Some(("y", 2))];
+    let result = html! { (rows) };
+    assert_eq!(result.into_string(), "x1y2");
+}
+
+#[test]
+fn join() {
+    let names = ["Rarity", "Applejack", "Pinkie & co"];
+    let result = html! {
+        p { (maud::join(&names, ", ")) }
+        p { (maud::join(1..=3, PreEscaped(" &middot; "))) }
+        p { (maud::join(Vec::<u8>::new(), ", ")) }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            "<p>Rarity, Applejack, Pinkie &amp; co</p>",
+            "<p>1 &middot; 2 &middot; 3</p>",
+            "<p></p>"
+        )
+    );
+}
+
+#[test]
+fn join_can_render_twice() {
+    let joined = maud::join(["a", "b"].iter(), "-");
+    let result = html! { (joined) "|" (joined) };
+    assert_eq!(result.into_string(), "a-b|a-b");
+}
+
+#[test]
+fn context() {
+    let values = [Some("x'"), None];
+    let result = html! { script { "f(" (values[0]) ");" } a href=(Some("javascript:")) {} };
+    assert_eq!(
+        result.into_string(),
+        r#"<script>f('x\u0027');</script><a href="about:invalid#maud-unsafe-url"></a>"#
+    );
+}
@@ -0,0 +1,136 @@
+use maud::{Children, Component, Markup, html, html_to};
+
+struct Card {
+    title: &'static str,
+}
+
+impl Component for Card {
+    fn render(&self, children: &Children) -> Markup {
+        html! {
+            .card {
+                h2 { (self.title) }
+                (children)
+                @if children.has_slot("footer") {
+                    footer { (children.slot("footer")) }
+                }
+            }
+        }
+    }
+}
+
+struct Badge;
+
+impl Component for Badge {
+    fn render_to(&self, children: &Children, buffer: &mut String) {
+        html_to!(buffer, span.badge {
+            @if children.is_empty() { "new" } @else { (children) }
+        });
+    }
+}
+
+#[test]
+fn children() {
+    let result = html! {
+        @Card title="Ponies" { p { "Ponies are small horses." } }
+    };
+    assert_eq!(
+        result.into_string(),
+        r#"<div class="card"><h2>Ponies</h2><p>Ponies are small horses.</p></div>"#
+    );
+}
+
+#[test]
+fn slots() {
+    let result = html! {
+        @Card title="Ponies" {
+            @slot footer { a href="/ponies" { "More" } }
+            "Small horses"
+        }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<div class="card"><h2>Ponies</h2>Small horses"#,
+            r#"<footer><a href="/ponies">More</a></footer></div>"#
+        )
+    );
+}
+
+#[test]
+fn void() {
+    let result = html! { @Badge; " " @Badge { "3" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<span class="badge">new</span> <span class="badge">3</span>"#
+    );
+}
+
+#[test]
+fn spliced_props() {
+    let titles = ["A & B", "C"];
+    let result = html! {
+        @for title in titles {
+            @Card title=(title) { (title.len()) }
+        }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<div class="card"><h2>A &amp; B</h2>5</div>"#,
+            r#"<div class="card"><h2>C</h2>1</div>"#
+        )
+    );
+}
+
+#[test]
+fn paths() {
+    mod ui {
+        use maud::{Children, Component, html_to};
+
+        pub struct Button {
+            pub kind: &'static str,
+            pub disabled: bool,
+        }
+
+        impl Component for Button {
+            fn render_to(&self, children: &Children, buffer: &mut String) {
+                html_to!(buffer, button type=(self.kind) disabled[self.disabled] { (children) });
+            }
+        }
+    }
+
+    let result = html! { @ui::Button kind="submit" disabled=false { "Go" } };
+    assert_eq!(result.into_string(), r#"<button type="submit">Go</button>"#);
+}
+
+#[test]
+fn nested() {
+    let result = html! {
+        @Card title="Outer" {
+            @Card title="Inner" { @Badge; }
+        }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<div class="card"><h2>Outer</h2><div class="card"><h2>Inner</h2>"#,
+            r#"<span class="badge">new</span></div></div>"#
+        )
+    );
+}
+
+#[test]
+fn render_outside_html() {
+    let card = Card { title: "Alone" };
+    assert_eq!(
+        card.render(&Children::empty()).into_string(),
+        r#"<div class="card"><h2>Alone</h2></div>"#
+    );
+}
+
+#[test]
+fn fmt_write() {
+    let mut output = String::new();
+    html_to!(&mut output as &mut dyn std::fmt::Write, @Badge { "!" }).unwrap();
+    assert_eq!(output, r#"<span class="badge">!</span>"#);
+}
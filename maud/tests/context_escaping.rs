@@ -0,0 +1,187 @@
+use maud::{PreEscaped, html};
+
+#[test]
+fn script_string() {
+    let name = "</script>\"";
+    let result = html! { script { "var name = " (name) ";" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<script>var name = '\u003c/script\u003e\u0022';</script>"#
+    );
+}
+
+#[test]
+fn script_inside_string_literal() {
+    let name = "Pinkie's party";
+    let result = html! { script { "var name = '" (name) "'; var party = " (name) ";" } };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<script>var name = 'Pinkie\u0027s party'; "#,
+            r#"var party = 'Pinkie\u0027s party';</script>"#
+        )
+    );
+}
+
+#[test]
+fn script_numbers() {
+    let count = 42;
+    let result = html! { script { "var count = " (count) ";" } };
+    assert_eq!(result.into_string(), "<script>var count = 42;</script>");
+}
+
+#[test]
+fn script_pre_escaped() {
+    let code = PreEscaped("alert(1)");
+    let result = html! { script { (code) } };
+    assert_eq!(result.into_string(), "<script>alert(1)</script>");
+}
+
+#[test]
+fn event_handler() {
+    let message = "\"); alert(\"pwned";
+    let result = html! { button onclick={ "greet(\"" (message) "\")" } { "Hi" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<button onclick="greet(&quot;\u0022); alert(\u0022pwned&quot;)">Hi</button>"#
+    );
+}
+
+#[test]
+fn event_handler_splice() {
+    let message = "Hi\"";
+    let result = html! { button onclick={ "alert(" (message) ")" } { "Hi" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<button onclick="alert('Hi\u0022')">Hi</button>"#
+    );
+}
+
+#[test]
+fn style() {
+    let color = "red; } body { display: none";
+    let result = html! {
+        style { "p { color: " (color) "; }" }
+        p style={ "color: " (color) } { "Hi" }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r"<style>p { color: red; \7d  body \7b  display: none; }</style>",
+            r#"<p style="color: red; \7d  body \7b  display: none">Hi</p>"#
+        )
+    );
+}
+
+#[test]
+fn style_declarations() {
+    let decl = "color: red; margin: 0 auto";
+    let result = html! {
+        p style=(decl) { "Hi" }
+    };
+    assert_eq!(
+        result.into_string(),
+        r#"<p style="color: red; margin: 0 auto">Hi</p>"#
+    );
+}
+
+#[test]
+fn url_scheme() {
+    let url = "javascript:alert(1)";
+    let result = html! { a href=(url) { "Click me" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<a href="about:invalid#maud-unsafe-url">Click me</a>"#
+    );
+}
+
+#[test]
+fn url_allowed() {
+    let url = "https://example.com/?a=1&b=2";
+    let result = html! { a href=(url) { "Click me" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<a href="https://example.com/?a=1&amp;b=2">Click me</a>"#
+    );
+}
+
+#[test]
+fn url_after_literal() {
+    let path = "javascript:alert(1)";
+    let result = html! { a href={ "/search?q=" (path) } { "Search" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<a href="/search?q=javascript:alert(1)">Search</a>"#
+    );
+}
+
+#[test]
+fn url_optional_attribute() {
+    let url = Some("javascript:alert(1)");
+    let result = html! { img src=[url]; };
+    assert_eq!(
+        result.into_string(),
+        r#"<img src="about:invalid#maud-unsafe-url">"#
+    );
+}
+
+#[test]
+fn display_in_script() {
+    let c = '"';
+    let result = html! { script { "var c = " (c) ";" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<script>var c = '\u0022';</script>"#
+    );
+}
+
+#[test]
+fn nested_element_is_html() {
+    let text = "<b>";
+    let result = html! { script { p { (text) } } };
+    assert_eq!(result.into_string(), "<script><p>&lt;b&gt;</p></script>");
+}
+
+#[test]
+fn escape_policies() {
+    let text = r#"<a title="it's">"#;
+    let result = html! {
+        p { #[escape(text)] (text) }
+        p { #[escape(double_quoted_attr)] (text) }
+        p { #[escape(single_quoted_attr)] (text) }
+        p title=#[escape(strict)] (text) { (text) }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<p>&lt;a title="it's"&gt;</p>"#,
+            "<p>&lt;a title=&quot;it's&quot;&gt;</p>",
+            r#"<p>&lt;a title="it&#39;s"&gt;</p>"#,
+            r#"<p title="&lt;a title=&quot;it&#39;s&quot;&gt;">&lt;a title=&quot;it's&quot;&gt;</p>"#,
+        )
+    );
+}
+
+#[test]
+fn escape_policy_leaves_markup_alone() {
+    let markup = html! { b title="it's" { "Rocks" } };
+    let result = html! { p { #[escape(strict)] (markup) } };
+    assert_eq!(result.into_string(), r#"<p><b title="it's">Rocks</b></p>"#);
+}
+
+#[test]
+fn escape_policy_after_url_literal() {
+    let query = "Maud's rocks";
+    let result = html! { a href={ "/search?q=" #[escape(strict)] (query) } { "Search" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<a href="/search?q=Maud&#39;s rocks">Search</a>"#
+    );
+}
+
+#[test]
+fn escape_policy_with_format_args() {
+    let name = "Maud";
+    let result = html! { p { #[escape(strict)] (format_args!("{name}'s")) } };
+    assert_eq!(result.into_string(), "<p>Maud&#39;s</p>");
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "csp")]
+
+use maud::{csp::Nonce, html};
+
+#[test]
+fn script_and_style() {
+    let nonce = Nonce::new("r4nd0m").unwrap();
+    let result = nonce.scope(|| {
+        html! {
+            style { "p { color: red; }" }
+            script src="app.js" {}
+            p { "Hi!" }
+        }
+    });
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<style nonce="r4nd0m">p { color: red; }</style>"#,
+            r#"<script src="app.js" nonce="r4nd0m"></script>"#,
+            "<p>Hi!</p>",
+        )
+    );
+}
+
+#[test]
+fn explicit_nonce() {
+    let nonce = Nonce::new("r4nd0m").unwrap();
+    let result = nonce.scope(|| html! { script nonce="other" {} });
+    assert_eq!(result.into_string(), r#"<script nonce="other"></script>"#);
+}
+
+#[test]
+fn spread_nonce() {
+    let nonce = Nonce::new("r4nd0m").unwrap();
+    let result = nonce.scope(|| {
+        html! {
+            script ..([("nonce", "other")]) {}
+            style ..([("media", "print")]) {}
+        }
+    });
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<script nonce="other"></script>"#,
+            r#"<style media="print" nonce="r4nd0m"></style>"#,
+        )
+    );
+}
+
+#[test]
+fn splice_nonce() {
+    let nonce = Nonce::new("r4nd0m").unwrap();
+    let result = html! { script nonce=(nonce) {} };
+    assert_eq!(result.into_string(), r#"<script nonce="r4nd0m"></script>"#);
+}
+
+#[test]
+fn outside_scope() {
+    let result = html! { script { "alert(1)" } };
+    assert_eq!(result.into_string(), "<script>alert(1)</script>");
+}
+
+#[test]
+fn header_value() {
+    let nonce = Nonce::new("r4nd0m").unwrap();
+    assert_eq!(nonce.source(), "'nonce-r4nd0m'");
+    assert_eq!(
+        nonce.header_value(),
+        "script-src 'nonce-r4nd0m'; style-src 'nonce-r4nd0m'"
+    );
+}
+
+#[cfg(feature = "axum")]
+#[test]
+fn axum_header() {
+    use axum_core::response::IntoResponse;
+
+    let nonce = Nonce::new("r4nd0m").unwrap();
+    let response = (nonce, html! {}).into_response();
+    assert_eq!(
+        response.headers()[http::header::CONTENT_SECURITY_POLICY],
+        "script-src 'nonce-r4nd0m'; style-src 'nonce-r4nd0m'"
+    );
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn stream_keeps_nonce() {
+    use futures_util::StreamExt;
+    use maud::html_stream;
+
+    let nonce = Nonce::new("r4nd0m").unwrap();
+    let stream = nonce.scope(|| {
+        html_stream! {
+            script { "a" }
+            (async { "" }.await)
+            style { "b" }
+        }
+    });
+    // Poll the stream outside of the scope, and on another thread
+    let chunks = std::thread::spawn(|| {
+        futures_executor::block_on(stream.map(|chunk| chunk.to_vec()).collect::<Vec<_>>())
+    })
+    .join()
+    .unwrap();
+    assert_eq!(
+        chunks.concat(),
+        br#"<script nonce="r4nd0m">a</script><style nonce="r4nd0m">b</style>"#
+    );
+}
@@ -0,0 +1,120 @@
+#![cfg(any(feature = "chrono", feature = "time"))]
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+    use maud::{datetime::Time, html};
+
+    #[test]
+    fn naive() {
+        let date = NaiveDate::from_ymd_opt(2025, 2, 2).unwrap();
+        let date_time = date.and_hms_milli_opt(9, 5, 0, 250).unwrap();
+        let result = html! { (date) " " (date_time.time()) " " (date_time) };
+        assert_eq!(
+            result.into_string(),
+            "2025-02-02 09:05:00.250 2025-02-02T09:05:00.250"
+        );
+    }
+
+    #[test]
+    fn offsets() {
+        let utc = Utc.with_ymd_and_hms(2025, 2, 2, 9, 30, 0).unwrap();
+        let sydney = FixedOffset::east_opt(11 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 2, 2, 20, 30, 0)
+            .unwrap();
+        let newfoundland = FixedOffset::west_opt(3 * 3600 + 1800)
+            .unwrap()
+            .with_ymd_and_hms(2025, 2, 2, 6, 0, 0)
+            .unwrap();
+        let result = html! { (utc) " " (sydney) " " (newfoundland) };
+        assert_eq!(
+            result.into_string(),
+            "2025-02-02T09:30:00Z 2025-02-02T20:30:00+11:00 2025-02-02T06:00:00-03:30"
+        );
+    }
+
+    #[test]
+    fn time_element() {
+        let date = NaiveDate::from_ymd_opt(2025, 2, 2).unwrap();
+        let result = html! {
+            (Time::new(date))
+            (Time::new(date).format("<%A>"))
+        };
+        assert_eq!(
+            result.into_string(),
+            concat!(
+                r#"<time datetime="2025-02-02">2025-02-02</time>"#,
+                r#"<time datetime="2025-02-02">&lt;Sunday&gt;</time>"#,
+            )
+        );
+    }
+
+    #[test]
+    fn script() {
+        let date = NaiveDate::from_ymd_opt(2025, 2, 2).unwrap();
+        let result = html! { script { "new Date(" (date) ")" } };
+        assert_eq!(
+            result.into_string(),
+            "<script>new Date('2025-02-02')</script>"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid date format")]
+    fn invalid_format() {
+        let date = NaiveDate::from_ymd_opt(2025, 2, 2).unwrap();
+        let _ = html! { (Time::new(date).format("%Q")) };
+    }
+}
+
+#[cfg(feature = "time")]
+mod time {
+    use maud::{datetime::Time, html};
+    use time::{Date, Month, PrimitiveDateTime, UtcOffset};
+
+    fn date() -> Date {
+        Date::from_calendar_date(2025, Month::February, 2).unwrap()
+    }
+
+    #[test]
+    fn primitive() {
+        let date_time =
+            PrimitiveDateTime::new(date(), time::Time::from_hms_milli(9, 5, 0, 250).unwrap());
+        let result = html! { (date()) " " (date_time.time()) " " (date_time) };
+        assert_eq!(
+            result.into_string(),
+            "2025-02-02 09:05:00.250 2025-02-02T09:05:00.250"
+        );
+    }
+
+    #[test]
+    fn offsets() {
+        let date_time = date().with_hms(9, 30, 0).unwrap();
+        let utc = date_time.assume_utc();
+        let sydney = date_time.assume_offset(UtcOffset::from_hms(11, 0, 0).unwrap());
+        let newfoundland = date_time.assume_offset(UtcOffset::from_hms(-3, -30, 0).unwrap());
+        let result = html! { (utc) " " (sydney) " " (newfoundland) };
+        assert_eq!(
+            result.into_string(),
+            "2025-02-02T09:30:00Z 2025-02-02T09:30:00+11:00 2025-02-02T09:30:00-03:30"
+        );
+    }
+
+    #[test]
+    fn time_element() {
+        let result = html! {
+            (Time::new(date()).format("[month repr:long] [day padding:none], [year]"))
+        };
+        assert_eq!(
+            result.into_string(),
+            r#"<time datetime="2025-02-02">February 2, 2025</time>"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid date format")]
+    fn invalid_format() {
+        let _ = html! { (Time::new(date()).format("[nonsense]")) };
+    }
+}
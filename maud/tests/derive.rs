@@ -0,0 +1,121 @@
+use maud::{Render, html};
+
+#[test]
+fn named_fields() {
+    #[derive(Render)]
+    #[maud(template = h1 { (title) } p.byline { "by " (author) })]
+    struct Post {
+        title: String,
+        author: &'static str,
+    }
+
+    let post = Post {
+        title: "Cupcakes & you".into(),
+        author: "Pinkie",
+    };
+    assert_eq!(
+        post.render().into_string(),
+        r#"<h1>Cupcakes &amp; you</h1><p class="byline">by Pinkie</p>"#
+    );
+}
+
+#[test]
+fn tuple_struct() {
+    #[derive(Render)]
+    #[maud(template = link rel="stylesheet" href=(_0);)]
+    struct Css(&'static str);
+
+    let result = html! { (Css("/style.css")) };
+    assert_eq!(
+        result.into_string(),
+        r#"<link rel="stylesheet" href="/style.css">"#
+    );
+}
+
+#[test]
+fn unit_struct() {
+    #[derive(Render)]
+    #[maud(template = hr;)]
+    struct Divider;
+
+    assert_eq!(Divider.render().into_string(), "<hr>");
+}
+
+#[test]
+fn generics() {
+    #[derive(Render)]
+    #[maud(template = ul { @for item in items { li { (item) } } })]
+    struct List<T: Render> {
+        items: Vec<T>,
+    }
+
+    let list = List {
+        items: vec![1, 2, 3],
+    };
+    assert_eq!(
+        list.render().into_string(),
+        "<ul><li>1</li><li>2</li><li>3</li></ul>"
+    );
+}
+
+#[test]
+fn enum_variants() {
+    #[derive(Render)]
+    enum Message {
+        #[maud(template = p.error { "Error: " (message) })]
+        Error {
+            message: &'static str,
+        },
+        #[maud(template = p { "Hello, " (_0) "!" })]
+        Greeting(&'static str),
+        #[maud(template = br;)]
+        Break,
+        Text(String),
+    }
+
+    let result = html! {
+        (Message::Error { message: "<oops>" })
+        (Message::Greeting("Rarity"))
+        (Message::Break)
+        (Message::Text("a < b".into()))
+    };
+    assert_eq!(
+        result.into_string(),
+        r#"<p class="error">Error: &lt;oops&gt;</p><p>Hello, Rarity!</p><br>a &lt; b"#
+    );
+}
+
+#[test]
+fn nested() {
+    #[derive(Render)]
+    #[maud(template = span { (name) })]
+    struct Name {
+        name: &'static str,
+    }
+
+    #[derive(Render)]
+    #[maud(template = p { "Hi, " (name) })]
+    struct Greeting {
+        name: Name,
+    }
+
+    let greeting = Greeting {
+        name: Name { name: "Twilight" },
+    };
+    assert_eq!(
+        greeting.render().into_string(),
+        "<p>Hi, <span>Twilight</span></p>"
+    );
+}
+
+#[test]
+fn unused_fields() {
+    #[derive(Render)]
+    #[maud(template = "constant")]
+    #[allow(dead_code)]
+    struct Constant {
+        ignored: u32,
+    }
+
+    assert_eq!(Constant { ignored: 0 }.render().into_string(), "constant");
+}
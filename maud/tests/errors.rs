@@ -1,3 +1,6 @@
+// The expected output leaves out the warnings from the `lint` feature
+#![cfg(not(feature = "lint"))]
+
 use trybuild::TestCases;
 
 #[test]
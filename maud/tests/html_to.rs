@@ -0,0 +1,93 @@
+use maud::{Render, html, html_to};
+use std::fmt;
+
+#[test]
+fn string() {
+    let mut buffer = String::from("<!-- header -->");
+    let name = "Pinkie <3";
+    html_to!(&mut buffer, {
+        p { "Hello, " (name) "!" }
+    });
+    assert_eq!(buffer, "<!-- header --><p>Hello, Pinkie &lt;3!</p>");
+}
+
+#[test]
+fn reborrow() {
+    fn header(buffer: &mut String, title: &str) {
+        html_to!(buffer, h1 { (title) });
+        html_to!(buffer, hr;);
+    }
+
+    let mut buffer = String::new();
+    header(&mut buffer, "Ponies");
+    assert_eq!(buffer, "<h1>Ponies</h1><hr>");
+}
+
+#[test]
+fn fmt_write() {
+    struct Output(Vec<String>);
+
+    impl fmt::Write for Output {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0.push(s.to_owned());
+            Ok(())
+        }
+    }
+
+    let mut output = Output(Vec::new());
+    let count = 3;
+    html_to!(&mut output, {
+        p { "There are " (count) " ponies" }
+    })
+    .unwrap();
+    assert_eq!(output.0, ["<p>There are ", "3", " ponies</p>"]);
+}
+
+#[test]
+fn fmt_write_error() {
+    struct Full;
+
+    impl fmt::Write for Full {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let mut full = Full;
+    assert!(html_to!(&mut full, p { "Hi" }).is_err());
+}
+
+#[test]
+fn formatter() {
+    struct Page(&'static str);
+
+    impl fmt::Display for Page {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            html_to!(f, title { (self.0) })
+        }
+    }
+
+    assert_eq!(Page("a & b").to_string(), "<title>a &amp; b</title>");
+}
+
+#[test]
+fn render_to() {
+    struct Greeting(&'static str);
+
+    impl Render for Greeting {
+        fn render_to(&self, buffer: &mut String) {
+            html_to!(buffer, p { "Hi, " (self.0) "!" });
+        }
+    }
+
+    let result = html! { (Greeting("Rarity")) };
+    assert_eq!(result.into_string(), "<p>Hi, Rarity!</p>");
+}
+
+#[test]
+fn context() {
+    let mut buffer = String::new();
+    let url = "javascript:alert(1)";
+    html_to!(&mut buffer, a href=(url) {});
+    assert_eq!(buffer, r#"<a href="about:invalid#maud-unsafe-url"></a>"#);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "std")]
+
+use maud::{PreEscaped, Render, html, html_to};
+use std::io;
+
+#[test]
+fn vec() {
+    let mut bytes = Vec::new();
+    let name = "Applejack & co";
+    html_to!(&mut bytes, {
+        p { "Hello, " (name) "!" }
+    })
+    .unwrap();
+    assert_eq!(bytes, b"<p>Hello, Applejack &amp; co!</p>");
+}
+
+#[test]
+fn cursor() {
+    let mut cursor = io::Cursor::new(Vec::new());
+    html_to!(&mut cursor, br;).unwrap();
+    assert_eq!(cursor.into_inner(), b"<br>");
+}
+
+#[test]
+fn string_still_returns_unit() {
+    // `String` keeps its fast path when `std` is enabled
+    let mut buffer = String::new();
+    let () = html_to!(&mut buffer, "hi");
+    assert_eq!(buffer, "hi");
+}
+
+#[test]
+fn error() {
+    struct Broken;
+
+    impl io::Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken pipe"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut broken = Broken;
+    let err = html_to!(&mut broken, p { (42) }).unwrap_err();
+    assert_eq!(err.to_string(), "broken pipe");
+}
+
+#[test]
+fn render_to_writer() {
+    let mut bytes = Vec::new();
+    "<pinkie>".render_to_writer(&mut bytes).unwrap();
+    PreEscaped("<pie>").render_to_writer(&mut bytes).unwrap();
+    html! { b { "!" } }.render_to_writer(&mut bytes).unwrap();
+    assert_eq!(bytes, b"&lt;pinkie&gt;<pie><b>!</b>");
+}
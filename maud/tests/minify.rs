@@ -0,0 +1,82 @@
+//! Run with `cargo test --features minify --test minify`. The other tests
+//! expect unminified output, so they fail with this feature enabled.
+
+#![cfg(feature = "minify")]
+
+use maud::html;
+
+#[test]
+fn whitespace() {
+    let result = html! {
+        p { "Hello,  \n    world! " span { "  hi  " } }
+        pre { "  keep\n    this" }
+        textarea { "and  this" }
+        script { "let  x = 1;" }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            "<p>Hello, world! <span> hi </span>",
+            "<pre>  keep\n    this</pre>",
+            "<textarea>and  this</textarea>",
+            "<script>let  x = 1;</script>",
+        )
+    );
+}
+
+#[test]
+fn attribute_quotes() {
+    let dynamic = "x";
+    let result = html! {
+        a #home.nav href="/" title="Go  home" data-x="a=b" data-y="" data-z=(dynamic) {}
+    };
+    assert_eq!(
+        result.into_string(),
+        r#"<a class="nav" id=home href=/ title="Go  home" data-x="a=b" data-y="" data-z="x"></a>"#
+    );
+}
+
+#[test]
+fn optional_end_tags() {
+    let result = html! {
+        ul { li { "a" } li { "b" } }
+        dl { dt { "c" } dd { "d" } }
+        table {
+            thead { tr { th { "e" } } }
+            tbody { tr { td { "f" } td { "g" } } tr { td { "h" } } }
+        }
+        div { p { "i" } p { "j" } span { "k" } }
+        a href="#" { p { "l" } }
+        select { option { "m" } option { "n" } }
+    };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            "<ul><li>a<li>b</ul>",
+            "<dl><dt>c<dd>d</dl>",
+            "<table><thead><tr><th>e",
+            "<tbody><tr><td>f<td>g<tr><td>h</table>",
+            "<div><p>i<p>j</p><span>k</span></div>",
+            "<a href=#><p>l</p></a>",
+            "<select><option>m<option>n</select>",
+        )
+    );
+}
+
+#[test]
+fn end_tags_kept_before_dynamic_content() {
+    let items = ["a", "b"];
+    let result = html! {
+        ul {
+            li { "first" }
+            @for item in &items {
+                li { (item) }
+            }
+        }
+        p { "x" } (items[0])
+    };
+    assert_eq!(
+        result.into_string(),
+        "<ul><li>first</li><li>a</li><li>b</li></ul><p>x</p>a"
+    );
+}
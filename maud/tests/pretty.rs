@@ -0,0 +1,55 @@
+use maud::{DOCTYPE, html};
+
+#[test]
+fn page() {
+    let markup = html! {
+        (DOCTYPE)
+        html {
+            head {
+                meta charset="utf-8";
+                title { "Ponies" }
+                style { "p b { color: red }" }
+            }
+            body {
+                h1 { "Hello, " b { "world" } "!" }
+                ul {
+                    li { "One" }
+                    li { p { "Two" } }
+                }
+                pre { "fn main() {\n    ...\n}" }
+                p { textarea { "  keep\nthis " } }
+            }
+        }
+    };
+    assert_eq!(
+        markup.pretty(),
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <title>Ponies</title>
+    <style>p b { color: red }</style>
+  </head>
+  <body>
+    <h1>Hello, <b>world</b>!</h1>
+    <ul>
+      <li>One</li>
+      <li>
+        <p>Two</p>
+      </li>
+    </ul>
+    <pre>fn main() {
+    ...
+}</pre>
+    <p><textarea>  keep
+this </textarea></p>
+  </body>
+</html>"#
+    );
+}
+
+#[test]
+fn inline_content_is_unchanged() {
+    let markup = html! { "Some " em { "inline" } div { "content" } };
+    assert_eq!(markup.pretty(), markup.into_string());
+}
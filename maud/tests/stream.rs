@@ -0,0 +1,140 @@
+#![cfg(feature = "stream")]
+
+use futures_executor::block_on;
+use futures_util::StreamExt;
+use maud::{Children, Component, html, html_stream, html_to};
+
+async fn slow<T>(value: T) -> T {
+    // Yield once, as a real database query would
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            std::task::Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    })
+    .await;
+    value
+}
+
+fn collect(stream: impl futures_util::Stream<Item = bytes::Bytes>) -> Vec<String> {
+    block_on(
+        stream
+            .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+            .collect(),
+    )
+}
+
+#[test]
+fn no_await() {
+    let name = "Fluttershy";
+    let chunks = collect(html_stream! { p { "Hi, " (name) "!" } });
+    assert_eq!(chunks, ["<p>Hi, Fluttershy!</p>"]);
+}
+
+#[test]
+fn empty() {
+    let chunks = collect(html_stream! {});
+    assert!(chunks.is_empty());
+}
+
+#[test]
+fn await_in_splice() {
+    let chunks = collect(html_stream! {
+        head { title { "Ponies" } }
+        body { p { (slow("<loaded>").await) } }
+    });
+    assert_eq!(
+        chunks,
+        [
+            "<head><title>Ponies</title></head><body><p>",
+            "&lt;loaded&gt;</p></body>"
+        ]
+    );
+}
+
+#[test]
+fn await_in_control_flow() {
+    let chunks = collect(html_stream! {
+        ul {
+            @for pony in slow(["Rarity", "Applejack"]).await {
+                li { (pony) }
+            }
+        }
+        @if slow(true).await {
+            p { "Done" }
+        }
+    });
+    assert_eq!(
+        chunks,
+        [
+            "<ul>",
+            "<li>Rarity</li><li>Applejack</li></ul>",
+            "<p>Done</p>"
+        ]
+    );
+}
+
+#[test]
+fn await_in_let() {
+    let chunks = collect(html_stream! {
+        h1 { "Count" }
+        @let count = slow(42).await;
+        p { (count) }
+    });
+    assert_eq!(chunks, ["<h1>Count</h1>", "<p>42</p>"]);
+}
+
+#[test]
+fn same_output_as_html() {
+    let url = "javascript:alert(1)";
+    let chunks = collect(html_stream! { a href=(url) { (slow(1).await) } });
+    assert_eq!(
+        chunks.concat(),
+        html! { a href=(url) { (1) } }.into_string()
+    );
+}
+
+#[test]
+fn component() {
+    struct Section;
+
+    impl Component for Section {
+        fn render_to(&self, children: &Children, buffer: &mut String) {
+            html_to!(buffer, section { (children) });
+        }
+    }
+
+    let chunks = collect(html_stream! {
+        @let name = slow("Fluttershy").await;
+        @Section { p { (name) } }
+    });
+    assert_eq!(chunks, ["<section><p>Fluttershy</p></section>"]);
+}
+
+#[test]
+fn component_after_await() {
+    struct Section;
+
+    impl Component for Section {
+        fn render_to(&self, children: &Children, buffer: &mut String) {
+            html_to!(buffer, section { (children) });
+        }
+    }
+
+    let chunks = collect(html_stream! {
+        h1 { "Ponies" }
+        (slow("Rarity").await)
+        @Section { p { "Fluttershy" } }
+    });
+    assert_eq!(
+        chunks,
+        [
+            "<h1>Ponies</h1>",
+            "Rarity<section><p>Fluttershy</p></section>"
+        ]
+    );
+}
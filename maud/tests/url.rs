@@ -0,0 +1,27 @@
+#![cfg(feature = "url")]
+
+use maud::html;
+use url::Url;
+
+#[test]
+fn href() {
+    let url = Url::parse("https://example.com/search?q=fish&chips=\"yes\"").unwrap();
+    let result = html! { a href=(url) { (url) } };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<a href="https://example.com/search?q=fish&amp;chips=%22yes%22">"#,
+            "https://example.com/search?q=fish&amp;chips=%22yes%22</a>",
+        )
+    );
+}
+
+#[test]
+fn unsafe_scheme() {
+    let url = Url::parse("javascript:alert(1)").unwrap();
+    let result = html! { a href=(url) { "Click" } };
+    assert_eq!(
+        result.into_string(),
+        r#"<a href="about:invalid#maud-unsafe-url">Click</a>"#
+    );
+}
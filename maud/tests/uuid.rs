@@ -0,0 +1,27 @@
+#![cfg(feature = "uuid")]
+
+use maud::html;
+use uuid::Uuid;
+
+#[test]
+fn render() {
+    let id = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+    let result = html! { div data-id=(id) { (id) } };
+    assert_eq!(
+        result.into_string(),
+        concat!(
+            r#"<div data-id="67e55044-10b1-426f-9247-bb680e5fe0c8">"#,
+            "67e55044-10b1-426f-9247-bb680e5fe0c8</div>",
+        )
+    );
+}
+
+#[test]
+fn script() {
+    let id = Uuid::nil();
+    let result = html! { script { "load(" (id) ")" } };
+    assert_eq!(
+        result.into_string(),
+        "<script>load('00000000-0000-0000-0000-000000000000')</script>"
+    );
+}
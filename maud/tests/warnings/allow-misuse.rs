@@ -0,0 +1,10 @@
+use maud::html;
+
+fn main() {
+    html! {
+        #[deny(missing_alt)] img src="a.png";
+    };
+    html! {
+        #[allow(missing_alt)] "text"
+    };
+}
@@ -0,0 +1,11 @@
+#![deny(deprecated)]
+
+use maud::html;
+
+fn main() {
+    let (a, b) = (true, false);
+    html! {
+        p title=(true) aria-hidden=(a && b) hidden=(a) {}
+        #[allow(bool_attribute)] p title=(false) {}
+    };
+}
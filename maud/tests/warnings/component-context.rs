@@ -0,0 +1,14 @@
+use maud::{html, Children, Component};
+
+struct Icon;
+
+impl Component for Icon {
+    fn render_to(&self, _: &Children, _: &mut String) {}
+}
+
+fn main() {
+    html! {
+        script { @Icon; }
+        style { @Icon { "a" } }
+    };
+}
@@ -0,0 +1,18 @@
+use maud::{html, Children, Component};
+
+struct Icon;
+
+impl Component for Icon {
+    fn render_to(&self, _: &Children, _: &mut String) {}
+}
+
+fn main() {
+    html! {
+        a title={ @Icon; } {}
+        @Icon {
+            @slot label { "a" }
+            @slot label { "b" }
+        }
+        @slot label { "c" }
+    };
+}
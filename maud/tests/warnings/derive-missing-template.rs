@@ -0,0 +1,13 @@
+use maud::Render;
+
+#[derive(Render)]
+struct Missing {
+    name: String,
+}
+
+#[derive(Render)]
+enum Choice {
+    Both(String, String),
+}
+
+fn main() {}
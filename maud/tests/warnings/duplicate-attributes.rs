@@ -0,0 +1,10 @@
+use maud::html;
+
+fn main() {
+    let flag = true;
+    html! {
+        div id="a" #b {}
+        input type="text" TYPE="email";
+        p.foo class[flag] {}
+    };
+}
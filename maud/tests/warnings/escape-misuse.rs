@@ -0,0 +1,19 @@
+use maud::html;
+
+fn main() {
+    let x = "";
+    html! {
+        p { #[escape(single_quote)] (x) }
+    };
+    html! {
+        #[escape(strict)] p { (x) }
+    };
+    html! {
+        script { #[escape(strict)] (x) }
+    };
+    html! {
+        a title=#[escape(text)] (x) {}
+        a title={ "x" #[escape(single_quoted_attr)] (x) } {}
+        a.{ #[escape(text)] (x) } {}
+    };
+}
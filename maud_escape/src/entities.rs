@@ -0,0 +1,167 @@
+//! Decoding character references, like `&amp;` and `&#169;`.
+
+use alloc::string::String;
+
+/// Replaces character references with the characters they stand for.
+///
+/// Numeric references are decoded, except for ones that stand for NUL or
+/// aren't valid characters, along with the more common named references.
+/// Anything else is left as it is.
+pub fn decode_entities(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match decode_one(rest) {
+            Some((c, len)) => {
+                output.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Decodes the reference at the start of the input, returning the character
+/// and the length of the reference.
+fn decode_one(input: &str) -> Option<(char, usize)> {
+    // Don't look any further than the longest reference that could be valid,
+    // so that text with a lot of `&`s doesn't take quadratic time
+    let end = input.bytes().take(MAX_LEN).position(|b| b == b';')?;
+    let reference = &input[1..end];
+    let c = if let Some(number) = reference.strip_prefix('#') {
+        // Check the digits first, as the parsers would also accept a `+`
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                u32::from_str_radix(hex, 16).ok()?
+            }
+            None if number.bytes().all(|b| b.is_ascii_digit()) => number.parse().ok()?,
+            _ => return None,
+        };
+        char::from_u32(code).filter(|&c| c != '\0')?
+    } else {
+        NAMED
+            .iter()
+            .find(|(name, _)| *name == reference)
+            .map(|&(_, c)| c)?
+    };
+    Some((c, end + 1))
+}
+
+/// The length of the longest reference that's decoded, such as
+/// `&#x0010ffff;`, allowing for a few leading zeros.
+const MAX_LEN: usize = 16;
+
+const NAMED: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("shy", '\u{ad}'),
+    ("zwj", '\u{200d}'),
+    ("zwnj", '\u{200c}'),
+    ("ensp", '\u{2002}'),
+    ("emsp", '\u{2003}'),
+    ("thinsp", '\u{2009}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("deg", '°'),
+    ("plusmn", '±'),
+    ("times", '×'),
+    ("divide", '÷'),
+    ("minus", '−'),
+    ("micro", 'µ'),
+    ("para", '¶'),
+    ("sect", '§'),
+    ("middot", '·'),
+    ("bull", '•'),
+    ("hellip", '…'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("sbquo", '‚'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("bdquo", '„'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("lsaquo", '‹'),
+    ("rsaquo", '›'),
+    ("prime", '′'),
+    ("Prime", '″'),
+    ("dagger", '†'),
+    ("Dagger", '‡'),
+    ("permil", '‰'),
+    ("cent", '¢'),
+    ("pound", '£'),
+    ("yen", '¥'),
+    ("euro", '€'),
+    ("curren", '¤'),
+    ("iexcl", '¡'),
+    ("iquest", '¿'),
+    ("frac14", '¼'),
+    ("frac12", '½'),
+    ("frac34", '¾'),
+    ("sup1", '¹'),
+    ("sup2", '²'),
+    ("sup3", '³'),
+    ("larr", '←'),
+    ("uarr", '↑'),
+    ("rarr", '→'),
+    ("darr", '↓'),
+    ("harr", '↔'),
+    ("lArr", '⇐'),
+    ("rArr", '⇒'),
+    ("hArr", '⇔'),
+    ("le", '≤'),
+    ("ge", '≥'),
+    ("ne", '≠'),
+    ("asymp", '≈'),
+    ("infin", '∞'),
+    ("check", '✓'),
+    ("cross", '✗'),
+    ("star", '☆'),
+    ("starf", '★'),
+    ("hearts", '♥'),
+    ("spades", '♠'),
+    ("clubs", '♣'),
+    ("diams", '♦'),
+    ("loz", '◊'),
+];
+
+#[cfg(test)]
+mod test {
+    use super::decode_entities as decode;
+
+    #[test]
+    fn references() {
+        assert_eq!(decode("Fish &amp; Chips"), "Fish & Chips");
+        assert_eq!(decode("&lt;&#60;&#x3C;&#X3c;"), "<<<<");
+        assert_eq!(decode("&copy; 2025&nbsp;&hellip;"), "© 2025\u{a0}…");
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(decode("a & b"), "a & b");
+        assert_eq!(
+            decode("&bogus; &#xZZ; &#0; &amp"),
+            "&bogus; &#xZZ; &#0; &amp"
+        );
+        assert_eq!(decode("&#+65; &#x+41;"), "&#+65; &#x+41;");
+        assert_eq!(
+            decode("&#xd800; &#x110000; &#x00000000000041;"),
+            "&#xd800; &#x110000; &#x00000000000041;"
+        );
+        assert_eq!(decode("&#x0010ffff;"), "\u{10ffff}");
+    }
+}
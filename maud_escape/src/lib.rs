@@ -0,0 +1,320 @@
+#![no_std]
+
+//! The HTML escaper shared by Maud's `html!` macro, which escapes literals at
+//! compile time, and its runtime, which escapes splices. The tables that both
+//! sides use to work out how to escape a splice live here too, along with the
+//! decoder for character references that turns escaped text back again, and
+//! the list of inline elements shared with Maud's tools.
+//!
+//! This is an internal crate. Please use [`maud`](https://docs.rs/maud/)
+//! instead.
+
+#![doc(html_root_url = "https://docs.rs/maud_escape/0.27.0")]
+
+extern crate alloc;
+
+mod entities;
+
+use alloc::string::String;
+
+pub use entities::decode_entities;
+
+/// Attributes that take a URL, whose values are checked for a safe scheme.
+pub const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "codebase",
+    "data",
+    "formaction",
+    "href",
+    "icon",
+    "longdesc",
+    "manifest",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+/// Elements where adding whitespace around them could change how the page
+/// looks. The pretty printer in `maud`, along with `html2maud`, `maud_fmt` and
+/// `maud_test`, leave the whitespace around these alone.
+pub const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "audio", "b", "bdi", "bdo", "br", "button", "canvas", "cite", "code", "data",
+    "del", "dfn", "em", "embed", "i", "iframe", "img", "input", "ins", "kbd", "label", "mark",
+    "math", "meter", "object", "output", "picture", "progress", "q", "ruby", "s", "samp", "select",
+    "small", "span", "strong", "sub", "sup", "svg", "textarea", "time", "u", "var", "video", "wbr",
+];
+
+/// The kind of value an attribute holds, which decides how a splice in it is
+/// escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeKind {
+    /// Plain text.
+    Text,
+    /// A URL, as listed in [`URL_ATTRIBUTES`].
+    Url,
+    /// CSS declarations, in a `style` attribute.
+    Style,
+    /// JavaScript code, in an `on*` event handler attribute.
+    Script,
+}
+
+impl AttributeKind {
+    /// Returns the kind of value that the attribute with the given name
+    /// holds. Names are compared without regard to ASCII case.
+    pub fn of(name: &str) -> AttributeKind {
+        if URL_ATTRIBUTES
+            .iter()
+            .any(|attr| name.eq_ignore_ascii_case(attr))
+        {
+            AttributeKind::Url
+        } else if name.eq_ignore_ascii_case("style") {
+            AttributeKind::Style
+        } else if name.len() > 2
+            && name[..2].eq_ignore_ascii_case("on")
+            && name.bytes().all(|b| b.is_ascii_alphabetic())
+        {
+            AttributeKind::Script
+        } else {
+            AttributeKind::Text
+        }
+    }
+}
+
+/// Escapes `&`, `<`, `>` and `"`, appending the result to `output`.
+pub fn escape_to_string(input: &str, output: &mut String) {
+    escape_bytes_to_string(input, b"&<>\"", output);
+}
+
+/// Escapes the given characters, which must be a subset of `&<>"'`, appending
+/// the result to `output`.
+pub fn escape_bytes_to_string<const N: usize>(input: &str, special: &[u8; N], output: &mut String) {
+    let bytes = input.as_bytes();
+    // The start of the text that hasn't been copied yet. Text between
+    // special characters is copied in one go, rather than a byte at a time.
+    let mut start = 0;
+    let mut escape = |i: usize, output: &mut String| {
+        if start < i {
+            output.push_str(&input[start..i]);
+        }
+        // Each arm pushes a constant, so the copy can be inlined
+        match bytes[i] {
+            b'&' => output.push_str("&amp;"),
+            b'<' => output.push_str("&lt;"),
+            b'>' => output.push_str("&gt;"),
+            b'"' => output.push_str("&quot;"),
+            byte => {
+                debug_assert_eq!(byte, b'\'', "`special` must be a subset of `&<>\"'`");
+                output.push_str("&#39;")
+            }
+        }
+        start = i + 1;
+    };
+
+    let mut chunks = bytes.chunks_exact(chunk::SIZE);
+    for (n, chunk) in chunks.by_ref().enumerate() {
+        let mut mask = chunk::special_mask(chunk.try_into().unwrap(), special);
+        while mask != 0 {
+            escape(
+                n * chunk::SIZE + (mask.trailing_zeros() / chunk::STRIDE) as usize,
+                output,
+            );
+            // Clear the lowest set bit
+            mask &= mask - 1;
+        }
+    }
+    let rest = bytes.len() - chunks.remainder().len();
+    for (i, b) in chunks.remainder().iter().enumerate() {
+        if special.contains(b) {
+            escape(rest + i, output);
+        }
+    }
+    output.push_str(&input[start..]);
+}
+
+/// Finds the characters to escape in 16 bytes at a time, using SSE2.
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+mod chunk {
+    use core::arch::x86_64::{
+        __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_or_si128, _mm_set1_epi8,
+        _mm_setzero_si128,
+    };
+
+    pub const SIZE: usize = 16;
+    /// The number of bits in the mask for each byte.
+    pub const STRIDE: u32 = 1;
+
+    /// Returns a mask with a bit set for each byte that's in `special`.
+    pub fn special_mask<const N: usize>(chunk: &[u8; SIZE], special: &[u8; N]) -> u64 {
+        // SAFETY: SSE2 is enabled, as checked by the `cfg` above, and
+        // `_mm_loadu_si128` doesn't need the chunk to be aligned
+        let mask = unsafe {
+            let chunk = _mm_loadu_si128(chunk.as_ptr().cast::<__m128i>());
+            let matches = special.iter().fold(_mm_setzero_si128(), |matches, &b| {
+                _mm_or_si128(matches, _mm_cmpeq_epi8(chunk, _mm_set1_epi8(b as i8)))
+            });
+            _mm_movemask_epi8(matches)
+        };
+        mask as u64
+    }
+}
+
+/// Finds the characters to escape in a word at a time, which works on any
+/// platform.
+#[cfg(not(all(target_arch = "x86_64", target_feature = "sse2")))]
+mod chunk {
+    pub const SIZE: usize = super::swar::SIZE;
+    pub const STRIDE: u32 = 8;
+
+    pub fn special_mask<const N: usize>(chunk: &[u8; SIZE], special: &[u8; N]) -> u64 {
+        super::swar::special_mask(chunk, special) as u64
+    }
+}
+
+#[cfg_attr(all(target_arch = "x86_64", target_feature = "sse2"), allow(dead_code))]
+mod swar {
+    pub const SIZE: usize = size_of::<usize>();
+    const ONES: usize = usize::MAX / 0xff;
+    const LOW_BITS: usize = ONES * 0x7f;
+
+    /// Returns a mask with the high bit set in each byte that's in
+    /// `special`.
+    pub fn special_mask<const N: usize>(chunk: &[u8; SIZE], special: &[u8; N]) -> usize {
+        // Little-endian, so that the first byte is the least significant
+        let word = usize::from_le_bytes(*chunk);
+        special
+            .iter()
+            .fold(0, |mask, &b| mask | zero_bytes(word ^ (ONES * b as usize)))
+    }
+
+    /// Returns a mask with the high bit set in each byte that's zero.
+    ///
+    /// Adding 0x7f to the low seven bits of a byte sets its high bit, unless
+    /// they're all zero. This can't carry into the next byte, so unlike the
+    /// usual `(x - 0x01) & !x & 0x80` trick, every byte is checked exactly.
+    fn zero_bytes(word: usize) -> usize {
+        !(((word & LOW_BITS) + LOW_BITS) | word | LOW_BITS)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{escape_bytes_to_string, escape_to_string, swar};
+    use alloc::{format, string::String};
+
+    #[test]
+    fn it_works() {
+        let mut s = String::new();
+        escape_to_string("<script>launchMissiles()</script>", &mut s);
+        assert_eq!(s, "&lt;script&gt;launchMissiles()&lt;/script&gt;");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "must be a subset"]
+    fn unsupported_special_character() {
+        escape_bytes_to_string("a=b", b"=", &mut String::new());
+    }
+
+    #[test]
+    fn every_position() {
+        // Put each special character at every offset within and across
+        // chunks, surrounded by text that looks almost special
+        for (special, escaped) in [
+            ('&', "&amp;"),
+            ('<', "&lt;"),
+            ('>', "&gt;"),
+            ('"', "&quot;"),
+        ] {
+            for before in 0..40 {
+                for after in [0, 1, 7, 8, 15, 16, 17] {
+                    let padding = |n| "%=?!é".chars().cycle().take(n).collect::<String>();
+                    let (before, after) = (padding(before), padding(after));
+                    let mut s = String::new();
+                    escape_to_string(&format!("{before}{special}{after}"), &mut s);
+                    assert_eq!(s, format!("{before}{escaped}{after}"));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn all_special() {
+        let mut s = String::new();
+        escape_to_string(&"<&>\"".repeat(20), &mut s);
+        assert_eq!(s, "&lt;&amp;&gt;&quot;".repeat(20));
+    }
+
+    #[test]
+    fn nothing_to_escape() {
+        let input = "Maud is a pony who likes rocks. ".repeat(10);
+        let mut s = String::new();
+        escape_to_string(&input, &mut s);
+        assert_eq!(s, input);
+    }
+
+    #[test]
+    fn other_characters() {
+        let mut s = String::new();
+        escape_bytes_to_string(&"Maud's <rock> & \"Boulder\" ".repeat(3), b"&'", &mut s);
+        assert_eq!(s, "Maud&#39;s <rock> &amp; \"Boulder\" ".repeat(3));
+    }
+
+    #[test]
+    fn swar() {
+        // 0xbc is `<` with the high bit set
+        let bytes = b"a<&b>\"c\xbc";
+        let chunk = &bytes[..swar::SIZE];
+        let mask = swar::special_mask(chunk.try_into().unwrap(), b"&<>\"");
+        for (i, &b) in chunk.iter().enumerate() {
+            let flagged = mask & (0x80 << (i * 8)) != 0;
+            assert_eq!(flagged, matches!(b, b'&' | b'<' | b'>' | b'"'), "byte {i}");
+        }
+        assert_eq!(mask & !(usize::MAX / 0xff * 0x80), 0);
+    }
+
+    /// Escapes a character at a time, which is slow but obviously correct.
+    fn escape_reference(input: &str, special: &[u8], output: &mut String) {
+        for c in input.chars() {
+            match c {
+                '&' if special.contains(&b'&') => output.push_str("&amp;"),
+                '<' if special.contains(&b'<') => output.push_str("&lt;"),
+                '>' if special.contains(&b'>') => output.push_str("&gt;"),
+                '"' if special.contains(&b'"') => output.push_str("&quot;"),
+                '\'' if special.contains(&b'\'') => output.push_str("&#39;"),
+                c => output.push(c),
+            }
+        }
+    }
+
+    /// Returns a random string, which has lots of characters to escape, and
+    /// characters whose UTF-8 encoding has a special character's low bits.
+    fn random_string(rng: &mut fastrand::Rng) -> String {
+        const CHARS: &[char] = &[
+            'a', 'Z', ' ', '\n', '\0', '&', '<', '>', '"', '\'', ';', '#', 'é', '¢', '§', '¼', '¾',
+            'Ħ', '\u{7f}', '\u{80}', '€', '🐴',
+        ];
+        let len = rng.usize(0..100);
+        (0..len).map(|_| CHARS[rng.usize(..CHARS.len())]).collect()
+    }
+
+    #[test]
+    fn fuzz() {
+        fn check<const N: usize>(input: &str, special: &[u8; N]) {
+            let (mut actual, mut expected) = (String::new(), String::new());
+            escape_bytes_to_string(input, special, &mut actual);
+            escape_reference(input, special, &mut expected);
+            assert_eq!(actual, expected, "escaping {special:?} in {input:?}");
+        }
+
+        let mut rng = fastrand::Rng::with_seed(0x6d61_7564);
+        for _ in 0..10_000 {
+            let input = random_string(&mut rng);
+            check(&input, b"&<>\"");
+            check(&input, b"&<>");
+            check(&input, b"&<>'");
+            check(&input, b"&<>\"'");
+        }
+    }
+}
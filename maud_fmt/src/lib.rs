@@ -0,0 +1,115 @@
+//! Formats the templates in Maud's `html!` macros.
+//!
+//! rustfmt leaves the insides of macros alone, so templates tend to drift
+//! into whatever style their last editor preferred. This crate reads them
+//! with the same parser as Maud itself, and prints them back in one style:
+//!
+//! * Blocks are indented by four spaces.
+//! * Block elements like `div` and `p`, control structures and components
+//!   each go on a line of their own. Text, splices and inline elements like
+//!   `a` and `strong` share lines, as long as they fit in 100 columns.
+//! * The `#id` shorthand comes straight after the element name, followed by
+//!   any `.class` shorthands, then the other attributes in their original
+//!   order.
+//! * Blank lines between items are kept, but runs of them are collapsed into
+//!   one.
+//!
+//! Rust code, such as the expression in a splice or the condition of an
+//! `@if`, is copied over as it is.
+//!
+//! ```rust
+//! let formatted =
+//!     maud_fmt::format_markups(r#"#main.card{h1{"Hello"}p{"Hi, " b{(name)} "!"}}"#).unwrap();
+//! assert_eq!(
+//!     formatted,
+//!     r#"#main.card {
+//!     h1 { "Hello" }
+//!     p { "Hi, " b { (name) } "!" }
+//! }"#,
+//! );
+//! ```
+//!
+//! # Limitations
+//!
+//! Comments would be lost when the template is parsed, so templates that
+//! contain comments are left untouched.
+
+mod print;
+mod source;
+
+use std::fmt::{self, Display, Formatter};
+
+use proc_macro2::{LineColumn, TokenStream};
+use proc_macro2_diagnostics::Diagnostic;
+use syn::parse::{ParseStream, Parser};
+
+use maud_syntax::{DiagnosticParse, Markups};
+
+pub use source::format_source;
+
+/// Formats the body of an `html!` macro.
+///
+/// The result isn't indented, and has no trailing newline.
+pub fn format_markups(input: &str) -> Result<String, Error> {
+    let tokens = input.parse::<TokenStream>().map_err(|error| Error {
+        message: error.to_string(),
+        start: error.span().start(),
+    })?;
+    if let Some(start) = source::find_comment(input, 0..input.len(), tokens.clone()) {
+        return Err(Error {
+            message: "templates with comments can't be formatted".into(),
+            start,
+        });
+    }
+    let markups = parse(Markups::diagnostic_parse, tokens)?;
+    Ok(print::print(&markups, ""))
+}
+
+/// Runs the given parser, failing on any diagnostics along the way.
+fn parse<T>(
+    parser: impl FnOnce(ParseStream, &mut Vec<Diagnostic>) -> syn::Result<T>,
+    tokens: TokenStream,
+) -> Result<T, Error> {
+    let mut diagnostics = Vec::new();
+    let data = Parser::parse2(|input: ParseStream| parser(input, &mut diagnostics), tokens)?;
+    match diagnostics.into_iter().next() {
+        Some(diagnostic) => Err(syn::Error::from(diagnostic).into()),
+        None => Ok(data),
+    }
+}
+
+/// An error that stopped a template from being formatted.
+#[derive(Debug, Clone)]
+pub struct Error {
+    message: String,
+    start: LineColumn,
+}
+
+impl Error {
+    /// The line where the error was found, starting from 1.
+    pub fn line(&self) -> usize {
+        self.start.line
+    }
+
+    /// The column where the error was found, starting from 1.
+    pub fn column(&self) -> usize {
+        self.start.column + 1
+    }
+}
+
+impl From<syn::Error> for Error {
+    fn from(error: syn::Error) -> Self {
+        Self {
+            message: error.to_string(),
+            start: error.span().start(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line(), self.column(), self.message)
+    }
+}
+
+impl std::error::Error for Error {}
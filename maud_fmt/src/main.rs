@@ -0,0 +1,126 @@
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process,
+};
+
+const USAGE: &str = "\
+Formats the templates in Maud's html! macros.
+
+Usage: maud_fmt [--check] [PATH]...
+
+Rewrites the Rust files given, and the Rust files in the directories given,
+in place. Reads from standard input and writes to standard output if no path
+is given.
+
+Options:
+  --check  Don't write anything, but list the files that would change, and
+           fail if there are any";
+
+fn main() {
+    let mut check = false;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            "--check" => check = true,
+            _ if arg.starts_with('-') => {
+                eprintln!("{USAGE}");
+                process::exit(2);
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if paths.is_empty() {
+        match format_stdin(check) {
+            Ok(changed) => process::exit(if check && changed { 1 } else { 0 }),
+            Err(error) => {
+                eprintln!("error: {error}");
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut failed = false;
+    for path in &paths {
+        if let Err(error) = find_files(path, &mut files) {
+            eprintln!("error: {}: {error}", path.display());
+            failed = true;
+        }
+    }
+    let mut changed = false;
+    for file in &files {
+        match format_file(file, check) {
+            Ok(true) => {
+                changed = true;
+                if check {
+                    println!("{}", file.display());
+                }
+            }
+            Ok(false) => {}
+            Err(error) => {
+                eprintln!("error: {}: {error}", file.display());
+                failed = true;
+            }
+        }
+    }
+    if failed || (check && changed) {
+        process::exit(1);
+    }
+}
+
+/// Formats standard input, returning whether anything changed.
+fn format_stdin(check: bool) -> Result<bool, Box<dyn Error>> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let output = maud_fmt::format_source(&input)?;
+    if !check {
+        print!("{output}");
+    }
+    Ok(output != input)
+}
+
+/// Formats a file, returning whether anything changed.
+fn format_file(path: &Path, check: bool) -> Result<bool, Box<dyn Error>> {
+    let input = fs::read_to_string(path)?;
+    let output = maud_fmt::format_source(&input)?;
+    if output == input {
+        return Ok(false);
+    }
+    if !check {
+        fs::write(path, output)?;
+    }
+    Ok(true)
+}
+
+/// Collects the Rust files at a path, skipping hidden directories and
+/// `target`.
+fn find_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for entry in entries {
+        let name = entry.file_name().unwrap_or_default().to_string_lossy();
+        if entry.is_dir() {
+            if !name.starts_with('.') && name != "target" {
+                find_files(&entry, files)?;
+            }
+        } else if name.ends_with(".rs") {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
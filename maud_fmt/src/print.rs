@@ -0,0 +1,713 @@
+//! Printing a parsed template in the canonical style.
+
+use std::ops::Range;
+
+use maud_escape::INLINE_ELEMENTS;
+use maud_syntax::{
+    Allow, Attribute, AttributeType, Component, ComponentBody, ComponentChild, ControlFlow,
+    ControlFlowKind, Element, ElementBody, HtmlName, HtmlNameFragment, HtmlNameOrMarkup, IfExpr,
+    IfOrBlock, Markup, Markups, MatchArm, NoElement, PropValue, Toggler,
+};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::spanned::Spanned;
+
+const INDENT: &str = "    ";
+pub const MAX_WIDTH: usize = 100;
+/// The longest an `@if` with an `@else` can be and still go on one line.
+const MAX_IF_ELSE_WIDTH: usize = 50;
+
+/// Stands in for a line break in a string literal while printing, so that
+/// it isn't indented.
+const LITERAL_NEWLINE: char = '\0';
+
+/// Prints a template, with each line starting with `indent`.
+pub fn print(markups: &Markups<Element>, indent: &str) -> String {
+    let mut printer = Printer {
+        output: String::new(),
+        indent,
+    };
+    printer.items(&markups.markups, 0);
+    printer.output.replace(LITERAL_NEWLINE, "\n")
+}
+
+struct Printer<'a> {
+    output: String,
+    indent: &'a str,
+}
+
+impl Printer<'_> {
+    fn line(&mut self, depth: usize) {
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+        self.output.push_str(self.indent);
+        for _ in 0..depth {
+            self.output.push_str(INDENT);
+        }
+    }
+
+    /// Adds text to the output. Any lines after the first are indented to
+    /// match the current line.
+    fn push(&mut self, text: &str) {
+        let start = self.output.rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.output[start..];
+        let indent = line[..line.len() - line.trim_start().len()].to_string();
+        for c in text.chars() {
+            self.output.push(c);
+            if c == '\n' {
+                self.output.push_str(&indent);
+            }
+        }
+    }
+
+    fn column(&self) -> usize {
+        let start = self.output.rfind('\n').map_or(0, |i| i + 1);
+        width(&self.output[start..])
+    }
+
+    /// Returns whether the text can be added to the current line.
+    fn fits(&self, text: &str) -> bool {
+        !text.contains(['\n', LITERAL_NEWLINE]) && self.column() + width(text) <= MAX_WIDTH
+    }
+
+    /// Writes the contents of a block, one line at a time.
+    ///
+    /// Items that share a line in the source stay together for as long as
+    /// they fit. Block elements, control structures and components get a line
+    /// to themselves.
+    fn items<T: Item>(&mut self, items: &[T], depth: usize) {
+        let mut previous_line = None;
+        let mut in_line = false;
+        for item in items {
+            let span = item.span();
+            let same_line = previous_line == Some(span.start().line);
+            let blank_line = previous_line.is_some_and(|line| span.start().line > line + 1);
+            previous_line = Some(span.end().line);
+            if item.is_skipped() {
+                continue;
+            }
+            if blank_line {
+                self.output.push('\n');
+                in_line = false;
+            }
+
+            if !item.can_inline() {
+                self.line(depth);
+                item.write(self, depth);
+                in_line = false;
+                continue;
+            }
+            let inline = item.inline();
+            if in_line && same_line && self.fits(&format!(" {inline}")) {
+                self.output.push(' ');
+                self.push(&inline);
+            } else {
+                self.line(depth);
+                in_line = self.fits(&inline);
+                if in_line {
+                    self.push(&inline);
+                } else {
+                    item.write(self, depth);
+                }
+            }
+        }
+    }
+
+    /// Writes a block in braces, on one line if it fits.
+    fn block<T: Item>(&mut self, items: &[T], depth: usize) {
+        if can_inline_block(items) {
+            let inline = inline_block(items);
+            if self.fits(&inline) {
+                self.push(&inline);
+                return;
+            }
+        }
+        self.broken_block(items, depth);
+    }
+
+    /// Writes a block in braces, with its contents on lines of their own.
+    fn broken_block<T: Item>(&mut self, items: &[T], depth: usize) {
+        if items.iter().all(Item::is_skipped) {
+            self.push("{}");
+            return;
+        }
+        self.output.push('{');
+        self.items(items, depth + 1);
+        self.line(depth);
+        self.output.push('}');
+    }
+
+    fn control_flow<E: Item>(&mut self, control_flow: &ControlFlow<E>, depth: usize) {
+        self.output.push('@');
+        match &control_flow.kind {
+            ControlFlowKind::Let(local) => self.push(&code(local)),
+            ControlFlowKind::If(if_) => self.if_expr(if_, depth),
+            ControlFlowKind::For(for_) => {
+                self.push(&format!("for {} in {} ", code(&for_.pat), code(&for_.expr),));
+                self.block(&for_.body.markups.markups, depth);
+            }
+            ControlFlowKind::While(while_) => {
+                self.output
+                    .push_str(&format!("while {} ", code(&while_.cond)));
+                self.block(&while_.body.markups.markups, depth);
+            }
+            ControlFlowKind::Match(match_) => {
+                self.output
+                    .push_str(&format!("match {} ", code(&match_.expr)));
+                if match_.arms.is_empty() {
+                    self.push("{}");
+                    return;
+                }
+                self.output.push('{');
+                for arm in &match_.arms {
+                    self.line(depth + 1);
+                    self.match_arm(arm, depth + 1);
+                }
+                self.line(depth);
+                self.output.push('}');
+            }
+        }
+    }
+
+    fn if_expr<E: Item>(&mut self, if_: &IfExpr<E>, depth: usize) {
+        if if_.else_branch.is_none() {
+            self.push(&format!("if {} ", code(&if_.cond)));
+            self.block(&if_.then_branch.markups.markups, depth);
+            return;
+        }
+
+        // Like rustfmt, only put short `if`/`else` chains on one line
+        let inline = inline_if(if_);
+        if width(&inline) <= MAX_IF_ELSE_WIDTH
+            && self.fits(&inline)
+            && if_branches(if_).into_iter().all(can_inline_block)
+        {
+            self.push(&inline);
+            return;
+        }
+        let mut if_ = if_;
+        loop {
+            self.push(&format!("if {} ", code(&if_.cond)));
+            self.broken_block(&if_.then_branch.markups.markups, depth);
+            let Some((_, _, else_branch)) = &if_.else_branch else {
+                break;
+            };
+            self.push(" @else ");
+            match &**else_branch {
+                IfOrBlock::If(else_if) => if_ = else_if,
+                IfOrBlock::Block(block) => {
+                    self.broken_block(&block.markups.markups, depth);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn match_arm<E: Item>(&mut self, arm: &MatchArm<E>, depth: usize) {
+        self.push(&match_arm_head(arm));
+        match &arm.body {
+            Markup::Block(block) => self.block(&block.markups.markups, depth),
+            body => {
+                let inline = body.inline();
+                if body.can_inline() && self.fits(&format!("{inline},")) {
+                    self.push(&inline);
+                } else {
+                    body.write(self, depth);
+                }
+                self.output.push(',');
+            }
+        }
+    }
+}
+
+/// Something that can appear in a block: a [`Markup`], or a child of a
+/// component.
+trait Item: ToTokens {
+    /// Whether this can share a line with other items.
+    fn can_inline(&self) -> bool;
+
+    /// Whether this has no effect, and can be left out.
+    fn is_skipped(&self) -> bool {
+        false
+    }
+
+    /// Returns this written on a single line.
+    fn inline(&self) -> String;
+
+    /// Writes this over as many lines as needed, starting at the current
+    /// position.
+    fn write(&self, printer: &mut Printer<'_>, depth: usize);
+}
+
+impl<E: Item> Item for Markup<E> {
+    fn can_inline(&self) -> bool {
+        match self {
+            Markup::Block(block) => can_inline_block(&block.markups.markups),
+            Markup::Lit(_) | Markup::Splice { .. } | Markup::Semi(_) => true,
+            Markup::Element(element) => element.can_inline(),
+            Markup::ControlFlow(_) | Markup::Component(_) => false,
+        }
+    }
+
+    fn is_skipped(&self) -> bool {
+        matches!(self, Markup::Semi(_))
+    }
+
+    fn inline(&self) -> String {
+        match self {
+            Markup::Block(block) => inline_block(&block.markups.markups),
+            Markup::Lit(lit) => literal(&lit.lit),
+            Markup::Splice { escape, expr, .. } => match escape {
+                Some(escape) => format!("#[escape({})] ({})", escape.policy, code(expr)),
+                None => format!("({})", code(expr)),
+            },
+            Markup::Element(element) => element.inline(),
+            Markup::ControlFlow(control_flow) => inline_control_flow(control_flow),
+            Markup::Component(component) => {
+                let mut output = component_head(component);
+                match &component.body {
+                    ComponentBody::Void(_) => output.push(';'),
+                    ComponentBody::Block { children, .. } => {
+                        output.push(' ');
+                        output.push_str(&inline_block(children));
+                    }
+                }
+                output
+            }
+            Markup::Semi(_) => String::new(),
+        }
+    }
+
+    fn write(&self, printer: &mut Printer<'_>, depth: usize) {
+        match self {
+            Markup::Block(block) => printer.block(&block.markups.markups, depth),
+            Markup::Element(element) => element.write(printer, depth),
+            Markup::ControlFlow(control_flow) => printer.control_flow(control_flow, depth),
+            Markup::Component(component) => {
+                printer.push(&component_head(component));
+                match &component.body {
+                    ComponentBody::Void(_) => printer.output.push(';'),
+                    ComponentBody::Block { children, .. } => {
+                        printer.output.push(' ');
+                        printer.block(children, depth);
+                    }
+                }
+            }
+            Markup::Lit(_) | Markup::Splice { .. } | Markup::Semi(_) => {
+                printer.push(&self.inline())
+            }
+        }
+    }
+}
+
+impl Item for Element {
+    fn can_inline(&self) -> bool {
+        let is_inline_element = self.name.as_ref().is_some_and(|name| {
+            INLINE_ELEMENTS.contains(&name.to_string().to_ascii_lowercase().as_str())
+        });
+        self.allows.is_empty()
+            && is_inline_element
+            && match &self.body {
+                ElementBody::Void(_) => true,
+                ElementBody::Block(block) => can_inline_block(&block.markups.markups),
+            }
+    }
+
+    fn inline(&self) -> String {
+        let mut output = String::new();
+        for allow in &self.allows {
+            output.push_str(&allow_text(allow));
+            output.push(' ');
+        }
+        output.push_str(&element_head(self));
+        match &self.body {
+            ElementBody::Void(_) => output.push(';'),
+            ElementBody::Block(block) => {
+                output.push(' ');
+                output.push_str(&inline_block(&block.markups.markups));
+            }
+        }
+        output
+    }
+
+    fn write(&self, printer: &mut Printer<'_>, depth: usize) {
+        for allow in &self.allows {
+            printer.push(&allow_text(allow));
+            printer.line(depth);
+        }
+        printer.push(&element_head(self));
+        match &self.body {
+            ElementBody::Void(_) => printer.output.push(';'),
+            ElementBody::Block(block) => {
+                printer.output.push(' ');
+                printer.block(&block.markups.markups, depth);
+            }
+        }
+    }
+}
+
+impl Item for NoElement {
+    fn can_inline(&self) -> bool {
+        match *self {}
+    }
+
+    fn inline(&self) -> String {
+        match *self {}
+    }
+
+    fn write(&self, _printer: &mut Printer<'_>, _depth: usize) {
+        match *self {}
+    }
+}
+
+impl Item for ComponentChild {
+    fn can_inline(&self) -> bool {
+        match self {
+            ComponentChild::Markup(markup) => markup.can_inline(),
+            ComponentChild::Slot(_) => false,
+        }
+    }
+
+    fn is_skipped(&self) -> bool {
+        match self {
+            ComponentChild::Markup(markup) => markup.is_skipped(),
+            ComponentChild::Slot(_) => false,
+        }
+    }
+
+    fn inline(&self) -> String {
+        match self {
+            ComponentChild::Markup(markup) => markup.inline(),
+            ComponentChild::Slot(slot) => format!(
+                "@slot {} {}",
+                slot.name,
+                inline_block(&slot.body.markups.markups),
+            ),
+        }
+    }
+
+    fn write(&self, printer: &mut Printer<'_>, depth: usize) {
+        match self {
+            ComponentChild::Markup(markup) => markup.write(printer, depth),
+            ComponentChild::Slot(slot) => {
+                printer.push(&format!("@slot {} ", slot.name));
+                printer.block(&slot.body.markups.markups, depth);
+            }
+        }
+    }
+}
+
+/// Returns whether a block can be written on one line: that is, whether its
+/// contents are inline, and on one line in the source.
+fn can_inline_block<T: Item>(items: &[T]) -> bool {
+    items.iter().all(Item::can_inline)
+        && items
+            .windows(2)
+            .all(|pair| pair[0].span().end().line == pair[1].span().start().line)
+}
+
+fn inline_block<T: Item>(items: &[T]) -> String {
+    let items = items
+        .iter()
+        .filter(|item| !item.is_skipped())
+        .map(Item::inline)
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        "{}".into()
+    } else {
+        format!("{{ {} }}", items.join(" "))
+    }
+}
+
+fn inline_control_flow<E: Item>(control_flow: &ControlFlow<E>) -> String {
+    match &control_flow.kind {
+        ControlFlowKind::Let(local) => format!("@{}", code(local)),
+        ControlFlowKind::If(if_) => format!("@{}", inline_if(if_)),
+        ControlFlowKind::For(for_) => format!(
+            "@for {} in {} {}",
+            code(&for_.pat),
+            code(&for_.expr),
+            inline_block(&for_.body.markups.markups),
+        ),
+        ControlFlowKind::While(while_) => format!(
+            "@while {} {}",
+            code(&while_.cond),
+            inline_block(&while_.body.markups.markups),
+        ),
+        ControlFlowKind::Match(match_) => {
+            let arms = match_
+                .arms
+                .iter()
+                .enumerate()
+                .map(|(i, arm)| {
+                    let mut output = match_arm_head(arm);
+                    output.push_str(&arm.body.inline());
+                    if i + 1 < match_.arms.len() && !matches!(arm.body, Markup::Block(_)) {
+                        output.push(',');
+                    }
+                    output
+                })
+                .collect::<Vec<_>>();
+            if arms.is_empty() {
+                format!("@match {} {{}}", code(&match_.expr))
+            } else {
+                format!("@match {} {{ {} }}", code(&match_.expr), arms.join(" "))
+            }
+        }
+    }
+}
+
+fn inline_if<E: Item>(if_: &IfExpr<E>) -> String {
+    let mut output = format!(
+        "if {} {}",
+        code(&if_.cond),
+        inline_block(&if_.then_branch.markups.markups),
+    );
+    if let Some((_, _, else_branch)) = &if_.else_branch {
+        output.push_str(" @else ");
+        match &**else_branch {
+            IfOrBlock::If(if_) => output.push_str(&inline_if(if_)),
+            IfOrBlock::Block(block) => output.push_str(&inline_block(&block.markups.markups)),
+        }
+    }
+    output
+}
+
+/// Returns the contents of each branch of an `@if`.
+fn if_branches<E>(if_: &IfExpr<E>) -> Vec<&[Markup<E>]> {
+    let mut branches = vec![if_.then_branch.markups.markups.as_slice()];
+    match if_
+        .else_branch
+        .as_ref()
+        .map(|(_, _, else_branch)| &**else_branch)
+    {
+        Some(IfOrBlock::If(else_if)) => branches.extend(if_branches(else_if)),
+        Some(IfOrBlock::Block(block)) => branches.push(&block.markups.markups),
+        None => {}
+    }
+    branches
+}
+
+fn match_arm_head<E>(arm: &MatchArm<E>) -> String {
+    let mut output = code(&arm.pat);
+    if let Some((_, guard)) = &arm.guard {
+        output.push_str(" if ");
+        output.push_str(&code(guard));
+    }
+    output.push_str(" => ");
+    output
+}
+
+fn component_head(component: &Component) -> String {
+    let mut output = format!("@{}", code(&component.path));
+    for prop in &component.props {
+        output.push_str(&format!(" {}=", prop.name));
+        match &prop.value {
+            PropValue::Lit(lit) => output.push_str(&literal(lit)),
+            PropValue::Splice { expr, .. } => output.push_str(&format!("({})", code(expr))),
+        }
+    }
+    output
+}
+
+fn allow_text(allow: &Allow) -> String {
+    let lints = allow
+        .lints
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    format!("#[allow({})]", lints.join(", "))
+}
+
+/// Writes the name and attributes of an element, with the `#id` and
+/// `.class` shorthands first.
+fn element_head(element: &Element) -> String {
+    // An explicit `class="..."` is merged with the shorthands in the order
+    // they're written, so any shorthands after it have to stay there
+    let class_attr = element.attrs.iter().position(|attr| {
+        matches!(
+            attr,
+            Attribute::Named { name, attr_type: AttributeType::Normal { .. } }
+                if name.to_string().eq_ignore_ascii_case("class")
+        )
+    });
+    let is_shorthand = |i: usize, attr: &Attribute| match attr {
+        Attribute::Id { .. } => true,
+        Attribute::Class { .. } => class_attr.is_none_or(|class_attr| i < class_attr),
+        Attribute::Named { .. } | Attribute::Spread { .. } => false,
+    };
+
+    let mut output = element.name.as_ref().map(html_name).unwrap_or_default();
+    for attr in &element.attrs {
+        if let Attribute::Id { .. } = attr {
+            // Since Rust 2021, `name#` is reserved syntax
+            if !output.is_empty() {
+                output.push(' ');
+            }
+            output.push_str(&attribute(attr));
+        }
+    }
+    for (i, attr) in element.attrs.iter().enumerate() {
+        if let Attribute::Class { .. } = attr
+            && is_shorthand(i, attr)
+        {
+            output.push_str(&attribute(attr));
+        }
+    }
+    for (i, attr) in element.attrs.iter().enumerate() {
+        if !is_shorthand(i, attr) {
+            if !output.is_empty() {
+                output.push(' ');
+            }
+            output.push_str(&attribute(attr));
+        }
+    }
+    output
+}
+
+fn attribute(attr: &Attribute) -> String {
+    match attr {
+        Attribute::Class { name, toggler, .. } => {
+            let mut output = format!(".{}", html_name_or_markup(name));
+            if let Some(toggler) = toggler {
+                output.push_str(&toggler_text(toggler));
+            }
+            output
+        }
+        Attribute::Id { name, .. } => {
+            let name = html_name_or_markup(name);
+            // Since Rust 2024, `#"` is reserved syntax too
+            if name.starts_with('"') {
+                format!("# {name}")
+            } else {
+                format!("#{name}")
+            }
+        }
+        Attribute::Named { name, attr_type } => {
+            let mut output = html_name(name);
+            match attr_type {
+                AttributeType::Normal { value, .. } => {
+                    output.push('=');
+                    output.push_str(&value.inline());
+                }
+                AttributeType::Optional { toggler, .. } => {
+                    output.push('=');
+                    output.push_str(&toggler_text(toggler));
+                }
+                AttributeType::Empty(Some(toggler)) => output.push_str(&toggler_text(toggler)),
+                AttributeType::Empty(None) => {}
+            }
+            output
+        }
+        Attribute::Spread { expr, .. } => format!("..({})", code(expr)),
+    }
+}
+
+fn toggler_text(toggler: &Toggler) -> String {
+    format!("[{}]", code(&toggler.cond))
+}
+
+fn html_name_or_markup(name: &HtmlNameOrMarkup) -> String {
+    match name {
+        HtmlNameOrMarkup::HtmlName(name) => html_name(name),
+        HtmlNameOrMarkup::Markup(markup) => markup.inline(),
+    }
+}
+
+/// Writes a name as it appeared in the source, keeping any quotes.
+fn html_name(name: &HtmlName) -> String {
+    let mut output = String::new();
+    for pair in name.name.pairs() {
+        match pair.value() {
+            HtmlNameFragment::Ident(ident) => output.push_str(&ident.to_string()),
+            HtmlNameFragment::LitInt(lit) => output.push_str(&literal(lit)),
+            HtmlNameFragment::LitStr(lit) => output.push_str(&literal(lit)),
+            HtmlNameFragment::Empty => {}
+        }
+        if let Some(punct) = pair.punct() {
+            output.push_str(&punct.to_string());
+        }
+    }
+    output
+}
+
+/// Returns a literal as it was written.
+fn literal(lit: &impl ToTokens) -> String {
+    lit.to_token_stream()
+        .to_string()
+        .replace('\n', &LITERAL_NEWLINE.to_string())
+}
+
+/// Returns a piece of Rust code as it was written.
+///
+/// If the code takes up more than one line, the lines after the first are
+/// indented relative to the first, so that they can be moved along with it.
+pub fn code(node: &impl ToTokens) -> String {
+    let span = node.span();
+    let Some(text) = span.source_text() else {
+        return node.to_token_stream().to_string();
+    };
+    if !text.contains('\n') {
+        return text;
+    }
+
+    // Line breaks in string literals have to stay as they are
+    let mut literals = Vec::new();
+    collect_multi_line_literals(node.to_token_stream(), &mut literals);
+    let start = span.byte_range().start;
+    let text = text
+        .char_indices()
+        .map(|(i, c)| {
+            let in_literal = literals.iter().any(|range| range.contains(&(start + i)));
+            if c == '\n' && in_literal {
+                LITERAL_NEWLINE
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+
+    let mut lines = text.split('\n');
+    let first = lines.next().unwrap_or_default();
+    let rest = lines.collect::<Vec<_>>();
+    let min_indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    // The last line is usually a closing bracket, at the same indentation as
+    // the first. Otherwise, assume that the other lines were indented by one
+    // more level, as in a method chain.
+    let base_indent = if rest
+        .iter()
+        .any(|line| line.trim_start().starts_with(['}', ')', ']']))
+    {
+        min_indent
+    } else {
+        min_indent.saturating_sub(INDENT.len())
+    };
+    let mut output = first.to_string();
+    for line in rest {
+        output.push('\n');
+        output.push_str(line.get(base_indent..).unwrap_or_default());
+    }
+    output
+}
+
+fn collect_multi_line_literals(tokens: TokenStream, ranges: &mut Vec<Range<usize>>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => collect_multi_line_literals(group.stream(), ranges),
+            TokenTree::Literal(lit) if lit.to_string().contains('\n') => {
+                ranges.push(lit.span().byte_range())
+            }
+            _ => {}
+        }
+    }
+}
+
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
@@ -0,0 +1,189 @@
+//! Finding and rewriting the templates in a Rust source file.
+
+use std::ops::Range;
+
+use maud_syntax::{DiagnosticParse, Markups};
+use proc_macro2::{Delimiter, Group, Ident, LineColumn, Span, TokenStream, TokenTree};
+use syn::{Expr, Token, parse::ParseStream};
+
+use crate::{
+    Error, parse,
+    print::{self, MAX_WIDTH, code},
+};
+
+/// Formats every `html!`, `html_to!` and `html_stream!` macro in a Rust
+/// source file.
+///
+/// Macros that don't parse as templates are left as they are. These include
+/// macros of the same name from other crates, and templates that contain
+/// comments.
+///
+/// ```rust
+/// let source = r#"fn hello(name: &str) -> Markup { html!{p{"Hello, "(name)"!"}} }"#;
+/// assert_eq!(
+///     maud_fmt::format_source(source).unwrap(),
+///     r#"fn hello(name: &str) -> Markup { html! { p { "Hello, " (name) "!" } } }"#,
+/// );
+/// ```
+pub fn format_source(source: &str) -> Result<String, Error> {
+    let tokens = source.parse::<TokenStream>().map_err(|error| Error {
+        message: error.to_string(),
+        start: error.span().start(),
+    })?;
+    let mut edits = Vec::new();
+    find_macros(source, tokens, &mut edits);
+
+    let mut output = source.to_string();
+    for (range, replacement) in edits.into_iter().rev() {
+        output.replace_range(range, &replacement);
+    }
+    Ok(output)
+}
+
+/// Collects the edits for each template in the token stream, from first to
+/// last.
+fn find_macros(source: &str, tokens: TokenStream, edits: &mut Vec<(Range<usize>, String)>) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < tokens.len() {
+        if let [
+            TokenTree::Ident(name),
+            TokenTree::Punct(bang),
+            TokenTree::Group(group),
+            ..,
+        ] = &tokens[i..]
+            && ["html", "html_to", "html_stream"]
+                .iter()
+                .any(|macro_name| name == macro_name)
+            && bang.as_char() == '!'
+        {
+            edits.extend(format_macro(source, name, bang.span(), group));
+            // Any templates inside this one are part of its Rust code
+            i += 3;
+            continue;
+        }
+        if let TokenTree::Group(group) = &tokens[i] {
+            find_macros(source, group.stream(), edits);
+        }
+        i += 1;
+    }
+}
+
+/// Formats a single macro call, returning the range to replace and what to
+/// replace it with.
+fn format_macro(
+    source: &str,
+    name: &Ident,
+    bang: Span,
+    group: &Group,
+) -> Option<(Range<usize>, String)> {
+    let (open, close, space) = match group.delimiter() {
+        Delimiter::Brace => ("{", "}", " "),
+        Delimiter::Parenthesis => ("(", ")", ""),
+        Delimiter::Bracket => ("[", "]", ""),
+        Delimiter::None => return None,
+    };
+    let range = group.span().byte_range();
+    let group_tokens = TokenStream::from(TokenTree::Group(group.clone()));
+    if find_comment(source, range.clone(), group_tokens).is_some() {
+        return None;
+    }
+
+    let line = source.lines().nth(name.span().start().line - 1)?;
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let column = bang.end().column + space.len();
+
+    let inner_indent = format!("{indent}    ");
+    let replacement = if name == "html_to" {
+        let (buffer, markups) = parse(
+            |input: ParseStream, diagnostics| {
+                let buffer = input.parse::<Expr>()?;
+                input.parse::<Token![,]>()?;
+                let markups = Markups::diagnostic_parse(input, diagnostics)?;
+                Ok((buffer, markups))
+            },
+            group.stream(),
+        )
+        .ok()?;
+        let buffer = code(&buffer);
+        let one_line = format!("{open}{buffer}, {}{close}", print::print(&markups, ""));
+        if fits(column, &one_line) {
+            one_line
+        } else if let [_] = markups.markups.as_slice() {
+            // Keep the start of the template next to the buffer, as in
+            // `html_to!(buffer, {`
+            let body = print::print(&markups, indent);
+            format!("{open}{buffer}, {}{close}", body.trim_start())
+        } else {
+            let body = print::print(&markups, &inner_indent);
+            format!("{open}\n{inner_indent}{buffer},\n{body}\n{indent}{close}")
+        }
+    } else {
+        let markups = parse(Markups::diagnostic_parse, group.stream()).ok()?;
+        let body = print::print(&markups, "");
+        let one_line = if body.is_empty() {
+            format!("{open}{close}")
+        } else {
+            format!("{open}{space}{body}{space}{close}")
+        };
+        if fits(column, &one_line) {
+            one_line
+        } else {
+            let body = print::print(&markups, &inner_indent);
+            format!("{open}\n{body}\n{indent}{close}")
+        }
+    };
+
+    Some((
+        bang.byte_range().end..range.end,
+        format!("{space}{replacement}"),
+    ))
+}
+
+fn fits(column: usize, text: &str) -> bool {
+    !text.contains('\n') && column + text.chars().count() <= MAX_WIDTH
+}
+
+/// Finds the first comment in the given range of the source, by looking for
+/// text that isn't whitespace between the tokens.
+pub fn find_comment(source: &str, range: Range<usize>, tokens: TokenStream) -> Option<LineColumn> {
+    let mut spans = Vec::new();
+    collect_spans(tokens, &mut spans);
+    spans.sort_by_key(|span| span.start);
+    spans.push(range.end..range.end);
+
+    let mut position = range.start;
+    for span in spans {
+        if span.start > position {
+            let gap = &source[position..span.start];
+            if !gap.trim().is_empty() {
+                let offset = position + gap.len() - gap.trim_start().len();
+                return Some(line_column(source, offset));
+            }
+        }
+        position = position.max(span.end);
+    }
+    None
+}
+
+fn collect_spans(tokens: TokenStream, spans: &mut Vec<Range<usize>>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                spans.push(group.span_open().byte_range());
+                collect_spans(group.stream(), spans);
+                spans.push(group.span_close().byte_range());
+            }
+            token => spans.push(token.span().byte_range()),
+        }
+    }
+}
+
+fn line_column(source: &str, offset: usize) -> LineColumn {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    LineColumn {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count(),
+    }
+}
@@ -0,0 +1,322 @@
+//! Each test formats a template, checks the result, then checks that
+//! formatting the result again leaves it as it is.
+
+#[track_caller]
+fn assert_formats(input: &str, expected: &str) {
+    let formatted = maud_fmt::format_markups(input).unwrap();
+    assert_eq!(formatted, expected, "formatted to:\n{formatted}");
+    assert_eq!(maud_fmt::format_markups(&formatted).unwrap(), formatted);
+}
+
+#[track_caller]
+fn assert_formats_source(input: &str, expected: &str) {
+    let formatted = maud_fmt::format_source(input).unwrap();
+    assert_eq!(formatted, expected, "formatted to:\n{formatted}");
+    assert_eq!(maud_fmt::format_source(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn empty() {
+    assert_formats("", "");
+}
+
+#[test]
+fn block_elements() {
+    assert_formats(
+        r#"div{h1{"Title"}ul{li{"One"}li{"Two"}}}"#,
+        r#"div {
+    h1 { "Title" }
+    ul {
+        li { "One" }
+        li { "Two" }
+    }
+}"#,
+    );
+}
+
+#[test]
+fn inline_items() {
+    assert_formats(
+        r#"p{"Some "  strong{"bold"}" text, and "(name)}"#,
+        r#"p { "Some " strong { "bold" } " text, and " (name) }"#,
+    );
+}
+
+#[test]
+fn line_breaks_between_inline_items() {
+    assert_formats(
+        r#"p {
+    "First line"
+    br;
+    "Second line"
+}"#,
+        r#"p {
+    "First line"
+    br;
+    "Second line"
+}"#,
+    );
+}
+
+#[test]
+fn long_lines() {
+    assert_formats(
+        r#"p { "A sentence that is long enough to push the paragraph well past the limit of one hundred columns" }"#,
+        r#"p {
+    "A sentence that is long enough to push the paragraph well past the limit of one hundred columns"
+}"#,
+    );
+}
+
+#[test]
+fn void_elements() {
+    assert_formats(
+        r#"input type="text" name="q" ; br; hr;"#,
+        r#"input type="text" name="q"; br;
+hr;"#,
+    );
+}
+
+#[test]
+fn empty_block() {
+    assert_formats("div{}", "div {}");
+}
+
+#[test]
+fn attribute_order() {
+    assert_formats(
+        r#"a href="/" .nav #home title="Home" .active { "Home" }"#,
+        r#"a #home.nav.active href="/" title="Home" { "Home" }"#,
+    );
+}
+
+#[test]
+fn nameless_elements() {
+    assert_formats(r#".card#main{"Hi"}"#, r#"#main.card { "Hi" }"#);
+}
+
+#[test]
+fn quoted_id() {
+    assert_formats(r#"div # "x-1" {}"#, r#"div # "x-1" {}"#);
+}
+
+#[test]
+fn class_shorthands_after_class_attribute() {
+    // `.b` is merged after `a`, so it can't be moved in front
+    assert_formats(r#"p .first class="a" .b {}"#, r#"p.first class="a" .b {}"#);
+}
+
+#[test]
+fn attribute_values() {
+    assert_formats(
+        r#"input value=(x) checked[done] disabled data-a=[maybe] .on[active];"#,
+        r#"input.on[active] value=(x) checked[done] disabled data-a=[maybe];"#,
+    );
+}
+
+#[test]
+fn escape_attributes() {
+    assert_formats(
+        r#"p title=#[escape( strict )](x){#[escape(text)]  (y)}"#,
+        r#"p title=#[escape(strict)] (x) { #[escape(text)] (y) }"#,
+    );
+}
+
+#[test]
+fn blank_lines() {
+    assert_formats(
+        r#"h1 { "One" }
+
+
+
+h2 { "Two" }
+h3 { "Three" }"#,
+        r#"h1 { "One" }
+
+h2 { "Two" }
+h3 { "Three" }"#,
+    );
+}
+
+#[test]
+fn control_structures() {
+    assert_formats(
+        r#"@for item in &items{li{(item)}}@while false{}@let x = 1;"#,
+        r#"@for item in &items {
+    li { (item) }
+}
+@while false {}
+@let x = 1;"#,
+    );
+}
+
+#[test]
+fn short_if_else() {
+    assert_formats(
+        r#"@if ok{"yes"}@else{"no"}"#,
+        r#"@if ok { "yes" } @else { "no" }"#,
+    );
+}
+
+#[test]
+fn long_if_else() {
+    assert_formats(
+        r#"@if let Some(user) = user { "Welcome back, " (user.name) } @else if guest { "Hello" } @else { "Sign in" }"#,
+        r#"@if let Some(user) = user {
+    "Welcome back, " (user.name)
+} @else if guest {
+    "Hello"
+} @else {
+    "Sign in"
+}"#,
+    );
+}
+
+#[test]
+fn match_arms() {
+    assert_formats(
+        r#"@match x{Some(x)=>{b{(x)}}None=>"none"}"#,
+        r#"@match x {
+    Some(x) => { b { (x) } }
+    None => "none",
+}"#,
+    );
+    assert_formats(
+        r#"@match x {
+    Some(x) => {
+        h2 { "Found" }
+        p { (x) }
+    },
+}"#,
+        r#"@match x {
+    Some(x) => {
+        h2 { "Found" }
+        p { (x) }
+    }
+}"#,
+    );
+}
+
+#[test]
+fn components() {
+    assert_formats(
+        r#"@Card title="Hi" {p{"Body"} @slot footer{"Foot"}}"#,
+        r#"@Card title="Hi" {
+    p { "Body" }
+    @slot footer { "Foot" }
+}"#,
+    );
+}
+
+#[test]
+fn multi_line_code() {
+    assert_formats(
+        r#"div {
+                (items
+                    .iter()
+                    .sum::<i32>())
+        }"#,
+        r#"div {
+    (items
+        .iter()
+        .sum::<i32>())
+}"#,
+    );
+}
+
+#[test]
+fn multi_line_literal() {
+    assert_formats(
+        r#"pre {
+            "one
+  two"
+        }"#,
+        r#"pre {
+    "one
+  two"
+}"#,
+    );
+}
+
+#[test]
+fn comments() {
+    let error = maud_fmt::format_markups("p { \"Hi\" }\n// note\n").unwrap_err();
+    assert_eq!((error.line(), error.column()), (2, 1));
+    assert_eq!(
+        error.to_string(),
+        "2:1: templates with comments can't be formatted",
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let error = maud_fmt::format_markups("p { \"Hi\" }\n  @let x = ;").unwrap_err();
+    assert_eq!((error.line(), error.column()), (2, 12));
+}
+
+#[test]
+fn source() {
+    assert_formats_source(
+        r#"fn page() -> Markup {
+    html!{h1{"Title"}p{"A paragraph that is long enough to be put on a line of its own"}}
+}
+"#,
+        r#"fn page() -> Markup {
+    html! {
+        h1 { "Title" }
+        p { "A paragraph that is long enough to be put on a line of its own" }
+    }
+}
+"#,
+    );
+}
+
+#[test]
+fn source_parentheses() {
+    assert_formats_source(
+        "let x = html!(p{\"Hi\"});\n",
+        "let x = html!(p { \"Hi\" });\n",
+    );
+}
+
+#[test]
+fn source_html_to() {
+    assert_formats_source(
+        "html_to!(buffer,p{\"Hi\"});\n",
+        "html_to!(buffer, p { \"Hi\" });\n",
+    );
+    assert_formats_source(
+        r#"fn f() {
+    html_to!(&mut buffer, h1 { "A heading" } p { "A paragraph that goes on for quite a while" });
+}
+"#,
+        r#"fn f() {
+    html_to!(
+        &mut buffer,
+        h1 { "A heading" }
+        p { "A paragraph that goes on for quite a while" }
+    );
+}
+"#,
+    );
+}
+
+#[test]
+fn source_nested_templates() {
+    assert_formats_source(
+        "html!{ul{@for x in xs{(html!{li{(x)}})}}}\n",
+        "html! {\n    ul {\n        @for x in xs { (html!{li{(x)}}) }\n    }\n}\n",
+    );
+}
+
+#[test]
+fn source_skips_comments_and_other_macros() {
+    let source = r#"html! {
+    // Keep this
+    p{"Hi"}
+}
+println!("{}", 1);
+html!{ not a template }
+"#;
+    assert_eq!(maud_fmt::format_source(source).unwrap(), source);
+}
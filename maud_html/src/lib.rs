@@ -0,0 +1,393 @@
+//! A forgiving HTML parser, shared by `html2maud` and `maud_test`.
+//!
+//! This handles the markup that people write by hand: unquoted attributes,
+//! end tags that are left out, and the like. It doesn't try to follow the
+//! HTML spec to the letter, and never fails; anything it can't make sense of
+//! is kept as text.
+//!
+//! This is an internal crate. Please use [`maud`](https://docs.rs/maud/)
+//! instead.
+
+#![doc(html_root_url = "https://docs.rs/maud_html/0.27.0")]
+
+use maud_escape::decode_entities;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+    Comment(String),
+    Doctype(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// The name of the element, in lowercase.
+    pub name: String,
+    pub attrs: Vec<Attr>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attr {
+    pub name: String,
+    /// The value of the attribute, or `None` if it was written without one.
+    pub value: Option<String>,
+}
+
+pub fn parse(input: &str) -> Vec<Node> {
+    let mut parser = Parser {
+        rest: input,
+        stack: Vec::new(),
+        top: Vec::new(),
+    };
+    parser.run();
+    parser.top
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+    /// The elements that are still open, innermost last.
+    stack: Vec<Element>,
+    top: Vec<Node>,
+}
+
+impl Parser<'_> {
+    fn run(&mut self) {
+        while !self.rest.is_empty() {
+            if let Some(comment) = self.rest.strip_prefix("<!--") {
+                let end = comment.find("-->").unwrap_or(comment.len());
+                self.push(Node::Comment(comment[..end].to_string()));
+                self.rest = comment.get(end + 3..).unwrap_or_default();
+            } else if let Some(doctype) = self.rest.strip_prefix("<!") {
+                let end = doctype.find('>').unwrap_or(doctype.len());
+                self.push(Node::Doctype(doctype[..end].trim().to_string()));
+                self.rest = doctype.get(end + 1..).unwrap_or_default();
+            } else if let Some(tag) = self.rest.strip_prefix("</")
+                && tag.starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                let end = tag.find('>').unwrap_or(tag.len());
+                let name = tag[..end].trim().to_ascii_lowercase();
+                self.rest = tag.get(end + 1..).unwrap_or_default();
+                self.end_tag(&name);
+            } else if let Some(tag) = self.rest.strip_prefix('<')
+                && tag.starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                self.rest = tag;
+                self.start_tag();
+            } else {
+                // Text runs up to the next thing that looks like a tag
+                let skip = usize::from(self.rest.starts_with('<'));
+                let end = self.rest[skip..]
+                    .find('<')
+                    .map_or(self.rest.len(), |i| i + skip);
+                let text = decode_entities(&self.rest[..end]);
+                self.rest = &self.rest[end..];
+                self.push_text(text);
+            }
+        }
+        while !self.stack.is_empty() {
+            self.close_innermost();
+        }
+    }
+
+    fn start_tag(&mut self) {
+        let name_len = self
+            .rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+            .unwrap_or(self.rest.len());
+        let name = self.rest[..name_len].to_ascii_lowercase();
+        self.rest = &self.rest[name_len..];
+        let attrs = self.attrs();
+        let self_closing = self.rest.starts_with("/>");
+        self.rest = self
+            .rest
+            .strip_prefix("/>")
+            .or_else(|| self.rest.strip_prefix('>'))
+            .unwrap_or(self.rest);
+
+        // Close any elements that this one implicitly ends, like a `p` before
+        // a `div`
+        while let Some(open) = self.stack.last()
+            && closes_implicitly(&open.name, &name)
+        {
+            self.close_innermost();
+        }
+
+        let mut element = Element {
+            name,
+            attrs,
+            children: Vec::new(),
+        };
+        if is_void(&element.name) || self_closing {
+            self.push(Node::Element(element));
+        } else if is_raw_text(&element.name) {
+            let end = find_end_tag(self.rest, &element.name).unwrap_or(self.rest.len());
+            let text = &self.rest[..end];
+            self.rest = &self.rest[end..];
+            if !text.is_empty() {
+                let text = if element.name == "script" || element.name == "style" {
+                    text.to_string()
+                } else {
+                    decode_entities(text)
+                };
+                element.children.push(Node::Text(text));
+            }
+            self.stack.push(element);
+        } else {
+            self.stack.push(element);
+        }
+    }
+
+    fn attrs(&mut self) -> Vec<Attr> {
+        let mut attrs = Vec::<Attr>::new();
+        loop {
+            self.rest = self.rest.trim_start();
+            if self.rest.is_empty() || self.rest.starts_with('>') || self.rest.starts_with("/>") {
+                return attrs;
+            }
+            let name_len = self
+                .rest
+                .find(|c: char| c.is_ascii_whitespace() || matches!(c, '=' | '>'))
+                .unwrap_or(self.rest.len())
+                // A stray `/`, as in `<a / >`, makes an empty name
+                .max(1);
+            let name = self.rest[..name_len].to_ascii_lowercase();
+            self.rest = self.rest[name_len..].trim_start();
+            let value = if let Some(rest) = self.rest.strip_prefix('=') {
+                let rest = rest.trim_start();
+                let (raw, rest) = match rest.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let end = rest[1..].find(quote).map_or(rest.len(), |i| i + 1);
+                        (&rest[1..end], rest.get(end + 1..).unwrap_or_default())
+                    }
+                    _ => {
+                        let end = rest
+                            .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                            .unwrap_or(rest.len());
+                        (&rest[..end], &rest[end..])
+                    }
+                };
+                self.rest = rest;
+                Some(decode_entities(raw))
+            } else {
+                None
+            };
+            // The first of any duplicate attributes wins
+            if name != "/" && !attrs.iter().any(|attr| attr.name == name) {
+                attrs.push(Attr { name, value });
+            }
+        }
+    }
+
+    fn end_tag(&mut self, name: &str) {
+        // An end tag with no matching start tag is ignored
+        if self.stack.iter().any(|open| open.name == name) {
+            while let Some(open) = self.stack.last() {
+                let matched = open.name == name;
+                self.close_innermost();
+                if matched {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn close_innermost(&mut self) {
+        let element = self.stack.pop().unwrap();
+        self.push(Node::Element(element));
+    }
+
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.top.push(node),
+        }
+    }
+
+    fn push_text(&mut self, text: String) {
+        let siblings = match self.stack.last_mut() {
+            Some(parent) => &mut parent.children,
+            None => &mut self.top,
+        };
+        if let Some(Node::Text(previous)) = siblings.last_mut() {
+            previous.push_str(&text);
+        } else {
+            siblings.push(Node::Text(text));
+        }
+    }
+}
+
+/// Finds the `</name` that ends a raw text element, ignoring case.
+fn find_end_tag(input: &str, name: &str) -> Option<usize> {
+    let bytes = input.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        bytes[i..].starts_with(b"</")
+            && bytes[i + 2..]
+                .get(..name.len())
+                .is_some_and(|candidate| candidate.eq_ignore_ascii_case(name.as_bytes()))
+    })
+}
+
+/// Returns whether starting a `next` element ends an open `open` element.
+fn closes_implicitly(open: &str, next: &str) -> bool {
+    match open {
+        "p" => P_CLOSERS.contains(&next),
+        "li" => next == "li",
+        "dt" | "dd" => matches!(next, "dt" | "dd"),
+        "td" | "th" => matches!(next, "td" | "th" | "tr" | "tbody" | "tfoot"),
+        "tr" => matches!(next, "tr" | "tbody" | "tfoot"),
+        "thead" | "tbody" => matches!(next, "tbody" | "tfoot"),
+        "option" => matches!(next, "option" | "optgroup"),
+        "optgroup" => next == "optgroup",
+        "rt" | "rp" => matches!(next, "rt" | "rp"),
+        _ => false,
+    }
+}
+
+pub fn is_void(name: &str) -> bool {
+    matches!(
+        name,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+/// Returns whether the contents of an element are text rather than markup.
+pub fn is_raw_text(name: &str) -> bool {
+    matches!(name, "script" | "style" | "textarea" | "title")
+}
+
+/// Elements that close a `p` element when they start.
+const P_CLOSERS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "table",
+    "ul",
+];
+
+#[cfg(test)]
+mod test {
+    use super::{Attr, Element, Node, parse};
+
+    fn element(name: &str, attrs: &[(&str, Option<&str>)], children: Vec<Node>) -> Node {
+        Node::Element(Element {
+            name: name.into(),
+            attrs: attrs
+                .iter()
+                .map(|(name, value)| Attr {
+                    name: name.to_string(),
+                    value: value.map(Into::into),
+                })
+                .collect(),
+            children,
+        })
+    }
+
+    fn text(text: &str) -> Node {
+        Node::Text(text.into())
+    }
+
+    #[test]
+    fn attributes() {
+        assert_eq!(
+            parse(r#"<INPUT Type=text value='a "b"' disabled data-x = "1 &amp; 2" type="number">"#),
+            [element(
+                "input",
+                &[
+                    ("type", Some("text")),
+                    ("value", Some("a \"b\"")),
+                    ("disabled", None),
+                    ("data-x", Some("1 & 2")),
+                ],
+                vec![],
+            )]
+        );
+    }
+
+    #[test]
+    fn implied_end_tags() {
+        assert_eq!(
+            parse("<ul><li>a<li>b</ul><p>c<div>d</div></span>"),
+            [
+                element(
+                    "ul",
+                    &[],
+                    vec![
+                        element("li", &[], vec![text("a")]),
+                        element("li", &[], vec![text("b")]),
+                    ],
+                ),
+                element("p", &[], vec![text("c")]),
+                element("div", &[], vec![text("d")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_text() {
+        assert_eq!(
+            parse("<script>if (a < b && c) {}</script><title>A &amp; B</title>"),
+            [
+                element("script", &[], vec![text("if (a < b && c) {}")]),
+                element("title", &[], vec![text("A & B")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_and_doctype() {
+        assert_eq!(
+            parse("<!DOCTYPE html><!-- hi --><br/>"),
+            [
+                Node::Doctype("DOCTYPE html".into()),
+                Node::Comment(" hi ".into()),
+                element("br", &[], vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn stray_angle_brackets() {
+        assert_eq!(parse("a < b > c"), [text("a < b > c")]);
+    }
+}
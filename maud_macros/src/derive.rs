@@ -0,0 +1,155 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2_diagnostics::Diagnostic;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, Token,
+    parse::{ParseStream, Parser},
+};
+
+use maud_syntax::{DiagnosticParse, Element, Markups};
+
+use crate::generate;
+
+pub fn derive_render(input: DeriveInput) -> syn::Result<TokenStream> {
+    let output_ident = Ident::new("__maud_output", Span::mixed_site());
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let template = find_template(&input.attrs)?.ok_or_else(|| {
+                Error::new_spanned(
+                    &input.ident,
+                    "expected a `#[maud(template = ...)]` attribute",
+                )
+            })?;
+            let pattern = fields_pattern(&data.fields);
+            let stmts = template.generate(output_ident.clone());
+            quote! {
+                #[allow(unused_variables)]
+                let Self #pattern = self;
+                #stmts
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let variant_ident = &variant.ident;
+                    let pattern = fields_pattern(&variant.fields);
+                    let stmts = match find_template(&variant.attrs)? {
+                        Some(template) => template.generate(output_ident.clone()),
+                        None => {
+                            if variant.fields.len() != 1 {
+                                return Err(Error::new_spanned(
+                                    variant,
+                                    "expected a `#[maud(template = ...)]` attribute, \
+                                     or exactly one field to render",
+                                ));
+                            }
+                            let field = match &variant.fields {
+                                Fields::Named(fields) => fields.named[0].ident.clone().unwrap(),
+                                _ => format_ident!("_0"),
+                            };
+                            quote!(::maud::Render::render_to(#field, #output_ident);)
+                        }
+                    };
+                    Ok(quote! {
+                        #[allow(unused_variables)]
+                        Self::#variant_ident #pattern => { #stmts }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "`Render` cannot be derived for unions",
+            ));
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::maud::Render for #name #ty_generics #where_clause {
+            #[allow(unused_mut)]
+            fn render_to(&self, mut #output_ident: &mut ::maud::macro_private::String) {
+                extern crate maud;
+                #body
+            }
+        }
+    })
+}
+
+/// A template given in a `#[maud(template = ...)]` attribute.
+struct Template {
+    markups: Markups<Element>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Template {
+    fn generate(self, output_ident: Ident) -> TokenStream {
+        let stmts = generate::generate(self.markups, output_ident);
+        let diag_tokens = self.diagnostics.into_iter().map(crate::warning::emit);
+        quote! {
+            #stmts
+            #(#diag_tokens)*
+        }
+    }
+}
+
+fn find_template(attrs: &[Attribute]) -> syn::Result<Option<Template>> {
+    let mut template = None;
+
+    for attr in attrs {
+        if !attr.path().is_ident("maud") {
+            continue;
+        }
+        if template.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate `#[maud]` attribute"));
+        }
+
+        let mut diagnostics = Vec::new();
+        let markups = Parser::parse2(
+            |input: ParseStream| {
+                let key = input.parse::<Ident>()?;
+                if key != "template" {
+                    return Err(Error::new(key.span(), "expected `template`"));
+                }
+                input.parse::<Token![=]>()?;
+                Markups::diagnostic_parse(input, &mut diagnostics)
+            },
+            attr.meta.require_list()?.tokens.clone(),
+        )?;
+        crate::run_lints(&markups, &mut diagnostics);
+
+        template = Some(Template {
+            markups,
+            diagnostics,
+        });
+    }
+
+    Ok(template)
+}
+
+/// Returns a pattern that binds each field to a variable of the same name.
+///
+/// Fields of tuple structs and variants are named `_0`, `_1`, and so on.
+fn fields_pattern(fields: &Fields) -> TokenStream {
+    match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!({ #(#names),* })
+        }
+        Fields::Unnamed(fields) => {
+            let names = (0..fields.unnamed.len()).map(|i| format_ident!("_{}", i));
+            quote!(( #(#names),* ))
+        }
+        Fields::Unit => quote!(),
+    }
+}
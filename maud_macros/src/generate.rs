@@ -1,26 +1,83 @@
-use proc_macro2::{Ident, Span, TokenStream};
+use std::cell::Cell;
+
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use proc_macro2_diagnostics::SpanDiagnosticExt;
 use quote::{ToTokens, quote};
-use syn::{Expr, Local, parse_quote, token::Brace};
+use syn::{
+    BinOp, Expr, ExprBinary, ExprLit, ExprParen, ExprUnary, Lit, Local, UnOp, parse_quote,
+    spanned::Spanned, token::Brace,
+};
 
-use crate::{ast::*, escape};
+use maud_escape as escape;
+use maud_syntax::*;
 
 pub fn generate(markups: Markups<Element>, output_ident: Ident) -> TokenStream {
-    let mut build = Builder::new(output_ident.clone());
-    Generator::new(output_ident).markups(markups, &mut build);
+    let generator = Generator::new(output_ident, false);
+    let mut build = generator.builder();
+    generator.element_markups(markups, None, &mut build);
+    build.finish()
+}
+
+/// Like `generate`, but awaits a flush before any code that contains an
+/// `.await`, so that the markup written so far can be sent while waiting.
+pub fn generate_stream(markups: Markups<Element>, output_ident: Ident) -> TokenStream {
+    let generator = Generator::new(output_ident, true);
+    let mut build = generator.builder();
+    generator.element_markups(markups, None, &mut build);
     build.finish()
 }
 
+/// Whether to shrink the output at compile time, by collapsing whitespace,
+/// dropping quotes around attribute values and omitting optional end tags.
+const MINIFY: bool = cfg!(feature = "minify");
+
+/// Whether to add the current Content Security Policy nonce to every
+/// `script` and `style` element.
+const CSP: bool = cfg!(feature = "csp");
+
 struct Generator {
     output_ident: Ident,
+    flush: bool,
+    context: Cell<Context>,
+    /// Whether whitespace in literals must be kept as is, for example in a
+    /// `pre` element.
+    preserve_whitespace: Cell<bool>,
+    /// Whether we're inside an attribute value, which is always written in
+    /// double quotes.
+    in_attribute: Cell<bool>,
+    /// Whether `#[allow(bool_attribute)]` is on this element or an enclosing
+    /// one.
+    allow_bool_attribute: Cell<bool>,
 }
 
 impl Generator {
-    fn new(output_ident: Ident) -> Generator {
-        Generator { output_ident }
+    fn new(output_ident: Ident, flush: bool) -> Generator {
+        Generator {
+            output_ident,
+            flush,
+            context: Cell::new(Context::Html),
+            preserve_whitespace: Cell::new(false),
+            in_attribute: Cell::new(false),
+            allow_bool_attribute: Cell::new(false),
+        }
+    }
+
+    fn with_context(&self, context: Context, f: impl FnOnce()) {
+        let old_context = self.context.replace(context);
+        f();
+        self.context.set(old_context);
+    }
+
+    fn preserving_whitespace(&self, preserve: bool, f: impl FnOnce()) {
+        let old = self
+            .preserve_whitespace
+            .replace(self.preserve_whitespace.get() || preserve);
+        f();
+        self.preserve_whitespace.set(old);
     }
 
     fn builder(&self) -> Builder {
-        Builder::new(self.output_ident.clone())
+        Builder::new(self.output_ident.clone(), self.flush)
     }
 
     fn markups<E: Into<Element>>(&self, markups: Markups<E>, build: &mut Builder) {
@@ -29,6 +86,40 @@ impl Generator {
         }
     }
 
+    /// Like `markups`, but omits optional end tags when minifying.
+    ///
+    /// `parent` is the name of the element that these markups are the whole
+    /// contents of, if that's known.
+    fn element_markups(
+        &self,
+        markups: Markups<Element>,
+        parent: Option<&str>,
+        build: &mut Builder,
+    ) {
+        let mut markups = markups
+            .markups
+            .into_iter()
+            .filter(|markup| !matches!(markup, Markup::Semi(_)))
+            .peekable();
+        while let Some(markup) = markups.next() {
+            match markup {
+                Markup::Element(element) if MINIFY => {
+                    let following = match markups.peek() {
+                        Some(Markup::Element(next)) => Following::Element(element_name(next)),
+                        Some(_) => Following::Unknown,
+                        None => match parent {
+                            Some(parent) => Following::EndOfParent(parent),
+                            None => Following::Unknown,
+                        },
+                    };
+                    let omit_end_tag = can_omit_end_tag(&element_name(&element), &following);
+                    self.element(element, omit_end_tag, build);
+                }
+                markup => self.markup(markup, build),
+            }
+        }
+    }
+
     fn markup<E: Into<Element>>(&self, markup: Markup<E>, build: &mut Builder) {
         match markup {
             Markup::Block(block) => {
@@ -46,10 +137,20 @@ impl Generator {
                     self.markups(block.markups, build);
                 }
             }
-            Markup::Lit(lit) => build.push_escaped(&lit.to_string()),
-            Markup::Splice { expr, .. } => self.splice(expr, build),
-            Markup::Element(element) => self.element(element.into(), build),
+            Markup::Lit(lit) => {
+                let lit = lit.to_string();
+                let context = self.context.get();
+                self.context.set(context.after_lit(&lit));
+                if MINIFY && context == Context::Html && !self.preserve_whitespace.get() {
+                    build.push_escaped(&collapse_whitespace(&lit));
+                } else {
+                    build.push_escaped(&lit);
+                }
+            }
+            Markup::Splice { escape, expr, .. } => self.splice(escape, expr, build),
+            Markup::Element(element) => self.element(element.into(), false, build),
             Markup::ControlFlow(control_flow) => self.control_flow(control_flow, build),
+            Markup::Component(component) => self.component(component, build),
             Markup::Semi(_) => {}
         }
     }
@@ -64,22 +165,186 @@ impl Generator {
         build.push_tokens(quote!({ #markups }));
     }
 
-    fn splice(&self, expr: Expr, build: &mut Builder) {
+    fn splice(&self, escape: Option<Escape>, expr: Expr, build: &mut Builder) {
         let output_ident = &self.output_ident;
-        build.push_tokens(quote!(maud::macro_private::render_to!(&(#expr), &mut #output_ident);));
+        let context = self.context.get();
+        self.context.set(context.after_splice());
+        let runtime_context = match escape {
+            None => context.to_runtime(),
+            Some(escape) if context == Context::Html => {
+                let policy = escape.policy.to_string();
+                let (variant, escapes_double_quote) = match policy.as_str() {
+                    "text" => (quote!(Text), false),
+                    "double_quoted_attr" => (quote!(DoubleQuotedAttr), true),
+                    "single_quoted_attr" => (quote!(SingleQuotedAttr), false),
+                    "strict" => (quote!(Strict), true),
+                    _ => unreachable!("checked by the parser"),
+                };
+                if self.in_attribute.get() && !escapes_double_quote {
+                    // The value could end the attribute and start another
+                    build.push_tokens(
+                        syn::Error::new_spanned(
+                            escape,
+                            format!(
+                                "`#[escape({policy})]` doesn't escape `\"`, so it can't be used \
+                                 in an attribute value"
+                            ),
+                        )
+                        .to_compile_error(),
+                    );
+                    return;
+                }
+                Some(quote!(maud::context::Context::Policy(maud::EscapePolicy::#variant)))
+            }
+            Some(escape) => {
+                build.push_tokens(
+                    syn::Error::new_spanned(
+                        escape,
+                        "`#[escape(...)]` can't be used in JavaScript, CSS or at the start of \
+                         a URL, where splices are already escaped to suit",
+                    )
+                    .to_compile_error(),
+                );
+                return;
+            }
+        };
+        match runtime_context {
+            None => build.push_tokens(
+                quote!(maud::macro_private::render_to!(&(#expr), &mut #output_ident);),
+            ),
+            Some(context) => build.push_tokens(quote!(
+                maud::macro_private::render_to!(&(#expr), &mut #output_ident, #context);
+            )),
+        }
     }
 
-    fn element(&self, element: Element, build: &mut Builder) {
+    fn element(&self, element: Element, omit_end_tag: bool, build: &mut Builder) {
+        let allow_bool_attribute = self.allow_bool_attribute.replace(
+            self.allow_bool_attribute.get()
+                || element
+                    .allows
+                    .iter()
+                    .flat_map(|allow| &allow.lints)
+                    .any(|lint| lint == "bool_attribute"),
+        );
+        let name = element_name(&element);
         let element_name = element.name.clone().unwrap_or_else(|| parse_quote!(div));
+        let has_nonce = element.attrs.iter().any(|attr| match attr {
+            Attribute::Named { name, .. } => name.to_string().eq_ignore_ascii_case("nonce"),
+            _ => false,
+        });
+        let add_nonce = CSP && CSP_ELEMENTS.contains(&name.as_str()) && !has_nonce;
         build.push_str("<");
         self.name(element_name.clone(), build);
-        self.attrs(element.attrs, build);
+        self.with_context(Context::Html, || {
+            let in_attribute = self.in_attribute.replace(true);
+            self.preserving_whitespace(true, || self.attrs(element.attrs, add_nonce, build));
+            self.in_attribute.set(in_attribute);
+        });
         build.push_str(">");
         if let ElementBody::Block(block) = element.body {
-            self.markups(block.markups, build);
-            build.push_str("</");
-            self.name(element_name, build);
-            build.push_str(">");
+            let context = Context::for_element(&name);
+            let preserve = PRESERVE_WHITESPACE_ELEMENTS.contains(&name.as_str());
+            self.with_context(context, || {
+                self.preserving_whitespace(preserve, || {
+                    self.element_markups(block.markups, Some(&name), build)
+                })
+            });
+            if !omit_end_tag {
+                build.push_str("</");
+                self.name(element_name, build);
+                build.push_str(">");
+            }
+        }
+        self.allow_bool_attribute.set(allow_bool_attribute);
+    }
+
+    fn component(&self, component: Component, build: &mut Builder) {
+        if self.context.get() != Context::Html {
+            // A component renders HTML, which would be escaped as the
+            // surrounding JavaScript or CSS, or not escaped at all
+            build.push_tokens(
+                syn::Error::new_spanned(
+                    &component.path,
+                    "components can't be used inside `script` or `style`",
+                )
+                .to_compile_error(),
+            );
+            return;
+        }
+        if self.flush && contains_await(component.body.to_token_stream()) {
+            build.push_tokens(
+                syn::Error::new_spanned(
+                    &component.path,
+                    "the children of a component are rendered synchronously, so they can't \
+                     contain `.await`",
+                )
+                .to_compile_error(),
+            );
+            return;
+        }
+
+        let output_ident = &self.output_ident;
+        let path = component.path;
+        let fields = component.props.into_iter().map(|prop| {
+            let name = prop.name;
+            let value = match prop.value {
+                PropValue::Lit(lit) => lit.into_token_stream(),
+                PropValue::Splice { expr, .. } => quote!((#expr)),
+            };
+            quote!(#name: #value)
+        });
+
+        let mut markups = Vec::new();
+        let mut slots = Vec::new();
+        if let ComponentBody::Block { children, .. } = component.body {
+            for child in children {
+                match child {
+                    ComponentChild::Markup(markup) => markups.push(markup),
+                    ComponentChild::Slot(slot) => {
+                        let name = slot.name.to_string();
+                        let body = self.children(slot.body.markups);
+                        slots.push(
+                            quote!((#name, &(#body) as &dyn Fn(&mut maud::macro_private::String))),
+                        );
+                    }
+                }
+            }
+        }
+        let body = if markups.is_empty() {
+            quote!(None)
+        } else {
+            let body = self.children(Markups { markups });
+            quote!(Some(&(#body) as &dyn Fn(&mut maud::macro_private::String)))
+        };
+
+        build.push_tokens(quote!(maud::macro_private::render_component(
+            &(#path { #(#fields),* }),
+            &maud::Children::new(#body, &[#(#slots),*]),
+            &mut #output_ident,
+        );));
+    }
+
+    /// Generates a closure that renders the children of a component.
+    ///
+    /// The closure can't be `async`, so it never flushes a stream. Whitespace
+    /// is kept as is if the component is inside a `pre` element.
+    fn children(&self, markups: Markups<Element>) -> TokenStream {
+        let output_ident = &self.output_ident;
+        let generator = Generator::new(output_ident.clone(), false);
+        generator
+            .preserve_whitespace
+            .set(self.preserve_whitespace.get());
+        generator
+            .allow_bool_attribute
+            .set(self.allow_bool_attribute.get());
+        let mut build = generator.builder();
+        generator.element_markups(markups, None, &mut build);
+        let stmts = build.finish();
+        quote! {
+            |#[allow(unused_mut)] mut #output_ident: &mut maud::macro_private::String| {
+                #stmts
+            }
         }
     }
 
@@ -95,12 +360,86 @@ impl Generator {
     }
 
     fn attr(&self, name: HtmlName, value: AttributeType, build: &mut Builder) {
+        if let AttributeType::Normal {
+            value: Markup::Splice { expr, .. },
+            ..
+        } = &value
+            && is_bool_expr(expr)
+            && !is_boolean_attr(&name.to_string())
+            && !self.allow_bool_attribute.get()
+        {
+            // `true` or `false` is rarely what the attribute expects, and
+            // usually means that a toggle was intended
+            build.push_tokens(crate::warning::emit(
+                expr.span()
+                    .warning(format!(
+                        "`bool` spliced into the value of `{name}`, which isn't a boolean \
+                         attribute"
+                    ))
+                    .help(format!(
+                        "this writes `true` or `false`; to add or leave out the attribute \
+                         instead, write `{name}[...]`"
+                    ))
+                    .note(
+                        "to silence this warning, add `#[allow(bool_attribute)]` before the \
+                         element",
+                    ),
+            ));
+        }
+        let context = Context::for_attr(&name.to_string());
         match value {
+            AttributeType::Normal {
+                value: Markup::Splice { escape, expr, .. },
+                ..
+            } if is_boolean_attr(&name.to_string()) => {
+                // Decide at runtime: a `bool` toggles the attribute, while
+                // anything else is its value
+                let inner_value: Expr = parse_quote!(inner_value);
+
+                let name_only = {
+                    let mut build = self.builder();
+                    build.push_str(" ");
+                    self.name(name.clone(), &mut build);
+                    build.finish()
+                };
+                let with_value = {
+                    let mut build = self.builder();
+                    build.push_str(" ");
+                    self.name(name, &mut build);
+                    build.push_str("=\"");
+                    self.with_context(context, || {
+                        self.splice(escape, parse_quote!(#inner_value.0), &mut build)
+                    });
+                    build.push_str("\"");
+                    build.finish()
+                };
+                build.push_tokens(quote!(
+                    match maud::macro_private::ChooseToggleOrValue(&(#expr)) {
+                        #inner_value => {
+                            use maud::macro_private::{ViaToggle as _, ViaValue as _};
+                            match (&&#inner_value).toggle_or_value().toggle(#inner_value.0) {
+                                ::core::option::Option::Some(true) => { #name_only }
+                                ::core::option::Option::Some(false) => {}
+                                ::core::option::Option::None => { #with_value }
+                            }
+                        }
+                    }
+                ));
+            }
+            AttributeType::Normal {
+                value: Markup::Lit(lit),
+                ..
+            } if MINIFY && can_unquote(&lit.to_string()) => {
+                build.push_str(" ");
+                self.name(name, build);
+                build.push_str("=");
+                build.push_escaped(&lit.to_string());
+            }
             AttributeType::Normal { value, .. } => {
                 build.push_str(" ");
                 self.name(name, build);
                 build.push_str("=\"");
-                self.markup(value, build);
+                self.with_context(context, || self.markup(value, build));
                 build.push_str("\"");
             }
             AttributeType::Optional {
@@ -114,7 +453,9 @@ impl Generator {
                     build.push_str(" ");
                     self.name(name, &mut build);
                     build.push_str("=\"");
-                    self.splice(inner_value.clone(), &mut build);
+                    self.with_context(context, || {
+                        self.splice(None, inner_value.clone(), &mut build)
+                    });
                     build.push_str("\"");
                     build.finish()
                 };
@@ -136,8 +477,23 @@ impl Generator {
         }
     }
 
-    fn attrs(&self, attrs: Vec<Attribute>, build: &mut Builder) {
-        let (classes, id, named_attrs) = split_attrs(attrs);
+    /// Writes the attributes of an element, along with the current Content
+    /// Security Policy nonce if `add_nonce` is set.
+    fn attrs(&self, attrs: Vec<Attribute>, add_nonce: bool, build: &mut Builder) {
+        let (classes, id, named_attrs, spreads) = split_attrs(attrs);
+
+        // Attributes written by the element itself, which a spread mustn't
+        // repeat
+        let mut static_names = vec![];
+        if !spreads.is_empty() {
+            if !classes.is_empty() {
+                static_names.push("class".to_string());
+            }
+            if id.is_some() {
+                static_names.push("id".to_string());
+            }
+            static_names.extend(named_attrs.iter().map(|(name, _)| name.to_string()));
+        }
 
         if !classes.is_empty() {
             let mut toggle_class_exprs = vec![];
@@ -171,7 +527,15 @@ impl Generator {
             build.push_str("\"");
         }
 
-        if let Some(id) = id {
+        if let Some(HtmlNameOrMarkup::HtmlName(id)) = &id
+            && MINIFY
+            && can_unquote(&id.to_string())
+        {
+            build.push_str(" ");
+            self.name(parse_quote!(id), build);
+            build.push_str("=");
+            self.name(id.clone(), build);
+        } else if let Some(id) = id {
             build.push_str(" ");
             self.name(parse_quote!(id), build);
             build.push_str("=\"");
@@ -182,6 +546,21 @@ impl Generator {
         for (name, attr_type) in named_attrs {
             self.attr(name, attr_type, build);
         }
+
+        let output_ident = &self.output_ident;
+        if !spreads.is_empty() {
+            let spreads_ident = Ident::new("__maud_spreads", Span::mixed_site());
+            // A spread can set the nonce too, so check for that at runtime
+            let nonce =
+                add_nonce.then(|| quote!(#spreads_ident.write_csp_nonce(&mut #output_ident);));
+            build.push_tokens(quote!({
+                let mut #spreads_ident = maud::macro_private::Spreads::new(&[#(#static_names),*]);
+                #(#spreads_ident.render((#spreads), &mut #output_ident);)*
+                #nonce
+            }));
+        } else if add_nonce {
+            build.push_tokens(quote!(maud::macro_private::write_csp_nonce(&mut #output_ident);));
+        }
     }
 
     fn control_flow<E: Into<Element>>(&self, control_flow: ControlFlow<E>, build: &mut Builder) {
@@ -314,10 +693,16 @@ fn split_attrs(
     Vec<(HtmlNameOrMarkup, Option<Expr>)>,
     Option<HtmlNameOrMarkup>,
     Vec<(HtmlName, AttributeType)>,
+    Vec<Expr>,
 ) {
     let mut classes = vec![];
     let mut id = None;
     let mut named_attrs = vec![];
+    let mut spreads = vec![];
+
+    let has_class_shorthand = attrs
+        .iter()
+        .any(|attr| matches!(attr, Attribute::Class { .. }));
 
     for attr in attrs {
         match attr {
@@ -325,25 +710,333 @@ fn split_attrs(
                 classes.push((name, toggler.map(|toggler| toggler.cond)))
             }
             Attribute::Id { name, .. } => id = Some(name),
+            // An explicit `class="..."` is merged with any `.class` shorthands
+            Attribute::Named {
+                name,
+                attr_type: AttributeType::Normal { value, .. },
+            } if has_class_shorthand && name.to_string().eq_ignore_ascii_case("class") => {
+                classes.push((HtmlNameOrMarkup::Markup(value), None))
+            }
             Attribute::Named { name, attr_type } => named_attrs.push((name, attr_type)),
+            Attribute::Spread { expr, .. } => spreads.push(expr),
+        }
+    }
+
+    (classes, id, named_attrs, spreads)
+}
+
+/// Returns whether splicing a `bool` into the given attribute, as in
+/// `disabled=(flag)`, should toggle the attribute rather than render `true` or
+/// `false`.
+fn is_boolean_attr(name: &str) -> bool {
+    BOOLEAN_ATTRS
+        .iter()
+        .any(|attr| name.eq_ignore_ascii_case(attr))
+}
+
+/// The boolean attributes in the HTML standard, which mean "on" when present
+/// and "off" when absent. Any other attribute renders a `bool` as text, since
+/// `"true"` and `"false"` can mean something there.
+const BOOLEAN_ATTRS: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+    "shadowrootclonable",
+    "shadowrootdelegatesfocus",
+    "shadowrootserializable",
+];
+
+/// Returns whether an expression is obviously a `bool`, going by its syntax.
+fn is_bool_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Bool(_), ..
+        }) => true,
+        Expr::Unary(ExprUnary {
+            op: UnOp::Not(_), ..
+        }) => true,
+        Expr::Binary(ExprBinary { op, .. }) => matches!(
+            op,
+            BinOp::Eq(_)
+                | BinOp::Ne(_)
+                | BinOp::Lt(_)
+                | BinOp::Le(_)
+                | BinOp::Gt(_)
+                | BinOp::Ge(_)
+                | BinOp::And(_)
+                | BinOp::Or(_)
+        ),
+        Expr::Paren(ExprParen { expr, .. }) => is_bool_expr(expr),
+        _ => false,
+    }
+}
+
+fn element_name(element: &Element) -> String {
+    element.name.as_ref().map_or_else(
+        || "div".to_string(),
+        |name| name.to_string().to_ascii_lowercase(),
+    )
+}
+
+/// Elements that need a nonce to run under a Content Security Policy.
+const CSP_ELEMENTS: &[&str] = &["script", "style"];
+
+/// Elements whose text is shown with its whitespace intact.
+const PRESERVE_WHITESPACE_ELEMENTS: &[&str] = &["pre", "textarea"];
+
+/// Replaces each run of whitespace with a single space.
+fn collapse_whitespace(lit: &str) -> String {
+    let mut output = String::with_capacity(lit.len());
+    let mut in_whitespace = false;
+    for c in lit.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_whitespace {
+                output.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            output.push(c);
+            in_whitespace = false;
+        }
+    }
+    output
+}
+
+/// Returns whether an attribute value can be written without quotes.
+fn can_unquote(value: &str) -> bool {
+    !value.is_empty()
+        && !value.bytes().any(|b| {
+            b.is_ascii_whitespace() || matches!(b, b'"' | b'\'' | b'=' | b'<' | b'>' | b'`')
+        })
+}
+
+/// What comes straight after an element, as far as can be told at compile
+/// time.
+enum Following<'a> {
+    Element(String),
+    /// The end of the given parent element.
+    EndOfParent(&'a str),
+    /// A splice, some text, control flow, or the end of a block whose parent
+    /// isn't known.
+    Unknown,
+}
+
+/// Returns whether the end tag of an element can be left out, following the
+/// rules in <https://html.spec.whatwg.org/multipage/syntax.html#optional-tags>.
+fn can_omit_end_tag(name: &str, following: &Following) -> bool {
+    let (next, end_of_parent) = match following {
+        Following::Element(next) => (next.as_str(), None),
+        Following::EndOfParent(parent) => ("", Some(*parent)),
+        Following::Unknown => return false,
+    };
+    let at_end = end_of_parent.is_some();
+    match name {
+        "li" => at_end || next == "li",
+        "dt" => matches!(next, "dt" | "dd"),
+        "dd" => at_end || matches!(next, "dt" | "dd"),
+        "p" => match end_of_parent {
+            Some(parent) => {
+                !matches!(
+                    parent,
+                    "a" | "audio" | "del" | "ins" | "map" | "noscript" | "video"
+                ) && !parent.contains('-')
+            }
+            None => P_CLOSERS.contains(&next),
+        },
+        "rt" | "rp" => at_end || matches!(next, "rt" | "rp"),
+        "optgroup" => at_end || matches!(next, "optgroup" | "hr"),
+        "option" => at_end || matches!(next, "option" | "optgroup" | "hr"),
+        "thead" => matches!(next, "tbody" | "tfoot"),
+        "tbody" => at_end || matches!(next, "tbody" | "tfoot"),
+        "tfoot" => at_end,
+        "tr" => at_end || next == "tr",
+        "td" | "th" => at_end || matches!(next, "td" | "th"),
+        _ => false,
+    }
+}
+
+/// Elements that close a `p` element when they start.
+const P_CLOSERS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "table",
+    "ul",
+];
+
+////////////////////////////////////////////////////////
+
+/// Where the generated code is about to write, for the purpose of choosing an
+/// escaping strategy for splices.
+///
+/// Inside JavaScript, the literals seen so far are scanned to work out whether
+/// a splice falls inside a string literal. This scan follows the template in
+/// source order, so it can be thrown off by control flow that opens a string
+/// in one branch but not another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Html,
+    Script(JsState),
+    Style,
+    Url,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsState {
+    Code,
+    String { quote: char, escaped: bool },
+}
+
+impl Context {
+    fn for_element(name: &str) -> Context {
+        if name.eq_ignore_ascii_case("script") {
+            Context::Script(JsState::Code)
+        } else if name.eq_ignore_ascii_case("style") {
+            Context::Style
+        } else {
+            Context::Html
         }
     }
 
-    (classes, id, named_attrs)
+    fn for_attr(name: &str) -> Context {
+        match escape::AttributeKind::of(name) {
+            escape::AttributeKind::Text => Context::Html,
+            escape::AttributeKind::Url => Context::Url,
+            escape::AttributeKind::Style => Context::Style,
+            escape::AttributeKind::Script => Context::Script(JsState::Code),
+        }
+    }
+
+    fn after_lit(self, lit: &str) -> Context {
+        match self {
+            Context::Script(mut state) => {
+                for c in lit.chars() {
+                    state = state.after_char(c);
+                }
+                Context::Script(state)
+            }
+            // Only a splice at the very start of a URL can change its scheme
+            Context::Url if !lit.is_empty() => Context::Html,
+            _ => self,
+        }
+    }
+
+    fn after_splice(self) -> Context {
+        match self {
+            Context::Url => Context::Html,
+            _ => self,
+        }
+    }
+
+    fn to_runtime(self) -> Option<TokenStream> {
+        match self {
+            Context::Html => None,
+            Context::Script(JsState::Code) => Some(quote!(maud::context::Context::Script)),
+            Context::Script(JsState::String { .. }) => {
+                Some(quote!(maud::context::Context::ScriptString))
+            }
+            Context::Style => Some(quote!(maud::context::Context::Style)),
+            Context::Url => Some(quote!(maud::context::Context::Url)),
+        }
+    }
+}
+
+impl JsState {
+    fn after_char(self, c: char) -> JsState {
+        match self {
+            JsState::Code => match c {
+                '"' | '\'' | '`' => JsState::String {
+                    quote: c,
+                    escaped: false,
+                },
+                _ => JsState::Code,
+            },
+            JsState::String {
+                quote,
+                escaped: false,
+            } => match c {
+                '\\' => JsState::String {
+                    quote,
+                    escaped: true,
+                },
+                c if c == quote => JsState::Code,
+                '\n' if quote != '`' => JsState::Code,
+                _ => self,
+            },
+            JsState::String {
+                quote,
+                escaped: true,
+            } => JsState::String {
+                quote,
+                escaped: false,
+            },
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////
 
 struct Builder {
     output_ident: Ident,
+    flush: bool,
     tokens: TokenStream,
     tail: String,
 }
 
 impl Builder {
-    fn new(output_ident: Ident) -> Builder {
+    fn new(output_ident: Ident, flush: bool) -> Builder {
         Builder {
             output_ident,
+            flush,
             tokens: TokenStream::new(),
             tail: String::new(),
         }
@@ -358,25 +1051,115 @@ impl Builder {
     }
 
     fn push_tokens(&mut self, tokens: TokenStream) {
-        self.cut();
+        let flush = self.flush && contains_await(tokens.clone());
+        self.cut(flush);
         self.tokens.extend(tokens);
     }
 
-    fn cut(&mut self) {
+    fn cut(&mut self, flush: bool) {
         if self.tail.is_empty() {
             return;
         }
         let push_str_expr = {
             let output_ident = self.output_ident.clone();
             let tail = &self.tail;
-            quote!(#output_ident.push_str(#tail);)
+            if flush {
+                quote! {
+                    #output_ident.push_str(#tail);
+                    #output_ident.flush().await;
+                }
+            } else {
+                quote!(#output_ident.push_str(#tail);)
+            }
         };
         self.tail.clear();
         self.tokens.extend(push_str_expr);
     }
 
     fn finish(mut self) -> TokenStream {
-        self.cut();
+        self.cut(false);
         self.tokens
     }
 }
+
+fn contains_await(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "await",
+        TokenTree::Group(group) => contains_await(group.stream()),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use maud::Render;
+    use proc_macro2::{Ident, Span};
+    use syn::parse::Parser;
+
+    use maud_syntax::{DiagnosticParse, Element, Markups};
+
+    use super::Builder;
+
+    fn parse(input: &str) -> Markups<Element> {
+        Parser::parse_str(
+            |input: syn::parse::ParseStream| Markups::diagnostic_parse(input, &mut Vec::new()),
+            input,
+        )
+        .unwrap()
+    }
+
+    fn generate(input: &str) -> String {
+        super::generate(parse(input), Ident::new("output", Span::call_site())).to_string()
+    }
+
+    fn generate_stream(input: &str) -> String {
+        super::generate_stream(parse(input), Ident::new("output", Span::call_site())).to_string()
+    }
+
+    #[test]
+    fn bool_attributes() {
+        let tokens = generate(
+            r#"
+                input disabled=(count == 0) checked=(flag) readonly=(!(a || b));
+                div aria-hidden=(a && b) title=(true) data-count=(count) {}
+                #[allow(bool_attribute)] div { p title=(false) {} }
+            "#,
+        );
+        let warnings = tokens
+            .match_indices("`bool` spliced into the value of ")
+            .map(|(i, _)| &tokens[i..i + 50])
+            .collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 2, "{warnings:#?}");
+        assert!(warnings[0].contains("`aria-hidden`"));
+        assert!(warnings[1].contains("`title`"));
+    }
+
+    #[test]
+    fn component_in_stream() {
+        let tokens = generate_stream("(slow().await) @Card { p { (name) } }");
+        assert!(!tokens.contains("compile_error"), "{tokens}");
+        let tokens = generate_stream("@Card { p { (slow().await) } }");
+        assert!(tokens.contains("can't contain `.await`"), "{tokens}");
+    }
+
+    #[test]
+    fn literals_are_escaped_like_splices() {
+        const CHARS: &[char] = &['a', ' ', '&', '<', '>', '"', '\'', ';', 'é', '¢', '¼', '🐴'];
+        let mut rng = fastrand::Rng::with_seed(0x6d61_7564);
+        for _ in 0..10_000 {
+            let len = rng.usize(0..100);
+            let input = (0..len)
+                .map(|_| CHARS[rng.usize(..CHARS.len())])
+                .collect::<String>();
+
+            // How `html!` escapes a literal at compile time...
+            let mut build = Builder::new(Ident::new("output", Span::call_site()), false);
+            build.push_escaped(&input);
+            // ...and how `maud` escapes a string at runtime
+            let mut rendered = String::new();
+            input.render_to(&mut rendered);
+
+            assert_eq!(build.tail, rendered, "escaping {input:?}");
+        }
+    }
+}
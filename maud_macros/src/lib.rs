@@ -1,47 +1,58 @@
 #![doc(html_root_url = "https://docs.rs/maud_macros/0.27.0")]
-#![allow(clippy::large_enum_variant)]
 #![allow(clippy::needless_pass_by_value)]
 
 extern crate proc_macro;
 
-mod ast;
-mod escape;
+mod derive;
 mod generate;
+#[cfg(feature = "lint")]
+mod lint;
+mod warning;
 
-use ast::DiagnosticParse;
+use maud_syntax::{DiagnosticParse, Element, Markups};
 use proc_macro2::{Ident, Span, TokenStream};
 use proc_macro2_diagnostics::Diagnostic;
 use quote::quote;
-use syn::parse::{ParseStream, Parser};
+use syn::{
+    Expr, Token,
+    parse::{ParseStream, Parser},
+};
 
 #[proc_macro]
 pub fn html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     expand(input.into()).into()
 }
 
+#[proc_macro]
+pub fn html_to(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_to(input.into()).into()
+}
+
+#[proc_macro]
+pub fn html_stream(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_stream(input.into()).into()
+}
+
+#[proc_macro_derive(Render, attributes(maud))]
+pub fn derive_render(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive::derive_render(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 fn expand(input: TokenStream) -> TokenStream {
     // Heuristic: the size of the resulting markup tends to correlate with the
     // code size of the template itself
     let size_hint = input.to_string().len();
 
-    let mut diagnostics = Vec::new();
-    let markups = match Parser::parse2(
-        |input: ParseStream| ast::Markups::diagnostic_parse(input, &mut diagnostics),
-        input,
-    ) {
+    let (markups, mut diagnostics) = match parse(Markups::diagnostic_parse, input) {
         Ok(data) => data,
-        Err(err) => {
-            let err = err.to_compile_error();
-            let diag_tokens = diagnostics.into_iter().map(Diagnostic::emit_as_expr_tokens);
-
-            return quote! {{
-                #err
-                #(#diag_tokens)*
-            }};
-        }
+        Err(err) => return err,
     };
+    run_lints(&markups, &mut diagnostics);
 
-    let diag_tokens = diagnostics.into_iter().map(Diagnostic::emit_as_expr_tokens);
+    let diag_tokens = diagnostics.into_iter().map(warning::emit);
 
     let output_ident = Ident::new("__maud_output", Span::mixed_site());
     let stmts = generate::generate(markups, output_ident.clone());
@@ -54,3 +65,81 @@ fn expand(input: TokenStream) -> TokenStream {
         maud::PreEscaped(#output_ident)
     }}
 }
+
+fn expand_to(input: TokenStream) -> TokenStream {
+    let ((buffer, markups), mut diagnostics) = match parse(
+        |input: ParseStream, diagnostics: &mut Vec<Diagnostic>| {
+            let buffer = input.parse::<Expr>()?;
+            input.parse::<Token![,]>()?;
+            let markups = Markups::diagnostic_parse(input, diagnostics)?;
+            Ok((buffer, markups))
+        },
+        input,
+    ) {
+        Ok(data) => data,
+        Err(err) => return err,
+    };
+    run_lints(&markups, &mut diagnostics);
+
+    let diag_tokens = diagnostics.into_iter().map(warning::emit);
+
+    let output_ident = Ident::new("__maud_output", Span::mixed_site());
+    let stmts = generate::generate(markups, output_ident.clone());
+    quote! {{
+        extern crate maud;
+        let mut #output_ident = maud::macro_private::buffer!(&mut *(#buffer));
+        #stmts
+        #(#diag_tokens)*
+        maud::macro_private::Buffer::finish(#output_ident)
+    }}
+}
+
+fn expand_stream(input: TokenStream) -> TokenStream {
+    let (markups, mut diagnostics) = match parse(Markups::diagnostic_parse, input) {
+        Ok(data) => data,
+        Err(err) => return err,
+    };
+    run_lints(&markups, &mut diagnostics);
+
+    let diag_tokens = diagnostics.into_iter().map(warning::emit);
+
+    let output_ident = Ident::new("__maud_output", Span::mixed_site());
+    let stmts = generate::generate_stream(markups, output_ident.clone());
+    quote! {{
+        extern crate maud;
+        #(#diag_tokens)*
+        maud::stream::MarkupStream::new(move |mut #output_ident: maud::stream::StreamBuffer| async move {
+            #stmts
+        })
+    }}
+}
+
+/// Runs the optional checks in the `lint` module, if enabled.
+fn run_lints(markups: &Markups<Element>, diagnostics: &mut Vec<Diagnostic>) {
+    #[cfg(feature = "lint")]
+    lint::lint(markups, diagnostics);
+    #[cfg(not(feature = "lint"))]
+    let _ = (markups, diagnostics);
+}
+
+/// Runs the given parser, collecting any diagnostics along the way.
+///
+/// On error, returns the tokens that should be emitted instead.
+fn parse<T>(
+    parser: impl FnOnce(ParseStream, &mut Vec<Diagnostic>) -> syn::Result<T>,
+    input: TokenStream,
+) -> Result<(T, Vec<Diagnostic>), TokenStream> {
+    let mut diagnostics = Vec::new();
+    match Parser::parse2(|input: ParseStream| parser(input, &mut diagnostics), input) {
+        Ok(data) => Ok((data, diagnostics)),
+        Err(err) => {
+            let err = err.to_compile_error();
+            let diag_tokens = diagnostics.into_iter().map(warning::emit);
+
+            Err(quote! {{
+                #err
+                #(#diag_tokens)*
+            }})
+        }
+    }
+}
@@ -0,0 +1,956 @@
+//! Optional checks for mistakes in a template.
+//!
+//! These are enabled by the `lint` feature. They only look at the elements and
+//! attributes written in the template, so markup produced by splices isn't
+//! checked.
+//!
+//! Each check has a name, which can be given to `#[allow(...)]` on an element
+//! to silence it for that element and its children.
+
+use proc_macro2::Span;
+use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
+use syn::spanned::Spanned;
+
+use maud_syntax::*;
+
+/// The names of all the checks.
+const LINTS: &[&str] = &[
+    "unknown_element",
+    "void_element",
+    "invalid_nesting",
+    "missing_alt",
+    "missing_href",
+    "missing_label",
+    "unknown_aria",
+    "invalid_role",
+    // Checked while generating code, so that it works without this feature
+    "bool_attribute",
+];
+
+pub fn lint(markups: &Markups<Element>, diagnostics: &mut Vec<Diagnostic>) {
+    let mut linter = Linter {
+        ancestors: Vec::new(),
+        allowed: Vec::new(),
+        label_targets: Vec::new(),
+        unlabeled_inputs: Vec::new(),
+        diagnostics,
+    };
+    linter.markups(markups);
+
+    // A label can come after the input that it refers to
+    for (id, diagnostic) in linter.unlabeled_inputs {
+        if !linter.label_targets.contains(&id) {
+            linter.diagnostics.push(diagnostic);
+        }
+    }
+}
+
+struct Linter<'a> {
+    /// The names of the enclosing elements, innermost last. `None` marks the
+    /// children of a component, whose parent is unknown.
+    ancestors: Vec<Option<String>>,
+    /// The lints silenced by `#[allow(...)]` on the enclosing elements.
+    allowed: Vec<String>,
+    /// The values of every `label for="..."` attribute seen so far.
+    label_targets: Vec<String>,
+    /// Inputs with an `id` but no other label, with the warning to emit if
+    /// no `label` refers to them.
+    unlabeled_inputs: Vec<(String, Diagnostic)>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn markups(&mut self, markups: &Markups<Element>) {
+        for markup in &markups.markups {
+            self.markup(markup);
+        }
+    }
+
+    fn markup(&mut self, markup: &Markup<Element>) {
+        match markup {
+            Markup::Block(block) => self.markups(&block.markups),
+            Markup::Element(element) => self.element(element),
+            Markup::ControlFlow(control_flow) => self.control_flow(control_flow),
+            Markup::Component(component) => self.component(component),
+            Markup::Lit(_) | Markup::Splice { .. } | Markup::Semi(_) => {}
+        }
+    }
+
+    fn control_flow(&mut self, control_flow: &ControlFlow<Element>) {
+        match &control_flow.kind {
+            ControlFlowKind::Let(_) => {}
+            ControlFlowKind::If(if_) => self.control_flow_if(if_),
+            ControlFlowKind::For(for_) => self.markups(&for_.body.markups),
+            ControlFlowKind::While(while_) => self.markups(&while_.body.markups),
+            ControlFlowKind::Match(match_) => {
+                for arm in &match_.arms {
+                    self.markup(&arm.body);
+                }
+            }
+        }
+    }
+
+    fn control_flow_if(&mut self, if_: &IfExpr<Element>) {
+        self.markups(&if_.then_branch.markups);
+        if let Some((_, _, else_branch)) = &if_.else_branch {
+            match &**else_branch {
+                IfOrBlock::If(if_) => self.control_flow_if(if_),
+                IfOrBlock::Block(block) => self.markups(&block.markups),
+            }
+        }
+    }
+
+    fn component(&mut self, component: &Component) {
+        if let ComponentBody::Block { children, .. } = &component.body {
+            self.ancestors.push(None);
+            for child in children {
+                match child {
+                    ComponentChild::Markup(markup) => self.markup(markup),
+                    ComponentChild::Slot(slot) => self.markups(&slot.body.markups),
+                }
+            }
+            self.ancestors.pop();
+        }
+    }
+
+    fn element(&mut self, element: &Element) {
+        let (name, span) = match &element.name {
+            Some(name) => (name.to_string().to_ascii_lowercase(), name.span()),
+            None => ("div".to_string(), element.span()),
+        };
+
+        let allowed_len = self.allowed.len();
+        for lint in element.allows.iter().flat_map(|allow| &allow.lints) {
+            let lint = lint.to_string();
+            if !LINTS.contains(&lint.as_str()) {
+                self.diagnostics
+                    .push(lint_span(element, &lint).warning(format!("unknown lint `{lint}`")));
+            }
+            self.allowed.push(lint);
+        }
+
+        // Elements inside SVG and MathML follow different rules
+        if !self.in_foreign_content() {
+            let attrs = Attrs::new(element);
+            self.check_name(&name, span);
+            self.check_body(&name, span, &element.body);
+            self.check_parent(&name, span);
+            self.check_required_attrs(&name, span, &attrs);
+            self.check_aria(&attrs);
+        }
+
+        if let ElementBody::Block(block) = &element.body {
+            self.ancestors.push(Some(name));
+            self.markups(&block.markups);
+            self.ancestors.pop();
+        }
+
+        self.allowed.truncate(allowed_len);
+    }
+
+    /// Emits a warning, unless its lint has been silenced.
+    fn warn(&mut self, lint: &str, diagnostic: Diagnostic) {
+        if let Some(diagnostic) = self.prepare(lint, diagnostic) {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn prepare(&self, lint: &str, diagnostic: Diagnostic) -> Option<Diagnostic> {
+        if self.allowed.iter().any(|allowed| allowed == lint) {
+            None
+        } else {
+            Some(diagnostic.note(format!(
+                "to silence this warning, add `#[allow({lint})]` before the element"
+            )))
+        }
+    }
+
+    fn check_name(&mut self, name: &str, span: Span) {
+        // Custom elements must contain a hyphen
+        if name.contains('-') || ELEMENTS.contains(&name) {
+            return;
+        }
+        let mut diagnostic = span.warning(format!("unknown element `{name}`"));
+        if let Some(suggestion) = suggest(name, ELEMENTS) {
+            diagnostic = diagnostic.help(format!("did you mean `{suggestion}`?"));
+        }
+        self.warn("unknown_element", diagnostic);
+    }
+
+    fn check_body(&mut self, name: &str, span: Span, body: &ElementBody) {
+        let is_void = VOID_ELEMENTS.contains(&name);
+        match body {
+            ElementBody::Block(_) if is_void => self.warn(
+                "void_element",
+                span.warning(format!(
+                    "`{name}` is a void element, and can't have contents"
+                ))
+                .help(format!("write `{name};` instead")),
+            ),
+            ElementBody::Void(_) if !is_void && ELEMENTS.contains(&name) => self.warn(
+                "void_element",
+                span.warning(format!("`{name}` is not a void element"))
+                    .help(format!("write `{name} {{}}` instead")),
+            ),
+            _ => {}
+        }
+    }
+
+    fn check_parent(&mut self, name: &str, span: Span) {
+        if let Some(parent) = self.parent() {
+            if let Some(allowed) = allowed_children(parent)
+                && !allowed.contains(&name)
+            {
+                let diagnostic = span
+                    .warning(format!("`{name}` can't be used inside `{parent}`"))
+                    .help(format!(
+                        "`{parent}` can only contain {}",
+                        allowed
+                            .iter()
+                            .map(|child| format!("`{child}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                self.warn("invalid_nesting", diagnostic);
+                return;
+            }
+
+            if PHRASING_PARENTS.contains(&parent) && FLOW_ELEMENTS.contains(&name) {
+                let diagnostic = span
+                    .warning(format!("`{name}` can't be used inside `{parent}`"))
+                    .help(format!(
+                        "`{parent}` can only contain text and inline elements"
+                    ));
+                self.warn("invalid_nesting", diagnostic);
+                return;
+            }
+        }
+
+        let nested = self
+            .enclosing()
+            .find(|ancestor| {
+                NO_NESTING
+                    .iter()
+                    .any(|group| group.contains(&name) && group.contains(ancestor))
+            })
+            .map(str::to_owned);
+        if let Some(ancestor) = nested {
+            self.warn(
+                "invalid_nesting",
+                span.warning(format!(
+                    "`{name}` can't be used inside another `{ancestor}`"
+                )),
+            );
+        }
+    }
+
+    fn check_required_attrs(&mut self, name: &str, span: Span, attrs: &Attrs) {
+        // A spread could add any attribute
+        if attrs.has_spread {
+            return;
+        }
+
+        match name {
+            "img" if !attrs.has("alt") => self.warn(
+                "missing_alt",
+                span.warning("`img` is missing an `alt` attribute")
+                    .help("describe the image with `alt=\"...\"`, or use `alt=\"\"` if it's only decoration"),
+            ),
+            "a" if !attrs.has("href") => self.warn(
+                "missing_href",
+                span.warning("`a` is missing an `href` attribute")
+                    .help("to make something clickable without linking anywhere, use `button`"),
+            ),
+            "input" => self.check_input_label(span, attrs),
+            "label" => {
+                if let Some(Some(target)) = attrs.get("for") {
+                    self.label_targets.push(target.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_input_label(&mut self, span: Span, attrs: &Attrs) {
+        let input_type = attrs.get("type").flatten().map(|t| t.to_ascii_lowercase());
+        if matches!(
+            input_type.as_deref(),
+            Some("hidden" | "submit" | "reset" | "button" | "image")
+        ) || attrs.has("aria-label")
+            || attrs.has("aria-labelledby")
+            || attrs.has("title")
+            || self.enclosing().any(|ancestor| ancestor == "label")
+        {
+            return;
+        }
+
+        let diagnostic = span.warning("`input` has no label").help(
+            "wrap it in a `label`, refer to its `id` from `label for=\"...\"`, or add `aria-label`",
+        );
+        match attrs.get("id") {
+            // Can't tell which label refers to a dynamic ID
+            Some(None) => {}
+            Some(Some(id)) => {
+                if let Some(diagnostic) = self.prepare("missing_label", diagnostic) {
+                    self.unlabeled_inputs.push((id.clone(), diagnostic));
+                }
+            }
+            None => self.warn("missing_label", diagnostic),
+        }
+    }
+
+    fn check_aria(&mut self, attrs: &Attrs) {
+        for attr in &attrs.attrs {
+            if attr.name.starts_with("aria-") && !ARIA_ATTRIBUTES.contains(&attr.name.as_str()) {
+                let mut diagnostic = attr
+                    .span
+                    .warning(format!("unknown ARIA attribute `{}`", attr.name));
+                if let Some(suggestion) = suggest(&attr.name, ARIA_ATTRIBUTES) {
+                    diagnostic = diagnostic.help(format!("did you mean `{suggestion}`?"));
+                }
+                self.warn("unknown_aria", diagnostic);
+            }
+        }
+
+        if let Some(attr) = attrs.attrs.iter().find(|attr| attr.name == "role")
+            && let Some(value) = &attr.value
+        {
+            for role in value.split_ascii_whitespace() {
+                if !ROLES.contains(&role) {
+                    let mut diagnostic = attr.span.warning(format!("invalid role `{role}`"));
+                    if let Some(suggestion) = suggest(role, ROLES) {
+                        diagnostic = diagnostic.help(format!("did you mean `{suggestion}`?"));
+                    }
+                    self.warn("invalid_role", diagnostic);
+                }
+            }
+        }
+    }
+
+    /// Returns the enclosing elements, innermost first, stopping at the
+    /// nearest component.
+    fn enclosing(&self) -> impl Iterator<Item = &str> {
+        self.ancestors
+            .iter()
+            .rev()
+            .map_while(|ancestor| ancestor.as_deref())
+    }
+
+    /// Returns the element whose content model applies here, skipping over
+    /// transparent elements like `a`.
+    fn parent(&self) -> Option<&str> {
+        self.enclosing()
+            .find(|ancestor| !TRANSPARENT_ELEMENTS.contains(ancestor))
+    }
+
+    fn in_foreign_content(&self) -> bool {
+        self.enclosing()
+            .any(|ancestor| ancestor == "svg" || ancestor == "math")
+    }
+}
+
+/// The attributes of an element, as far as they can be known at compile time.
+struct Attrs {
+    attrs: Vec<Attr>,
+    has_spread: bool,
+}
+
+struct Attr {
+    name: String,
+    /// The value of the attribute, if it's a literal.
+    value: Option<String>,
+    span: Span,
+}
+
+impl Attrs {
+    fn new(element: &Element) -> Attrs {
+        let mut attrs = Vec::new();
+        let mut has_spread = false;
+        for attr in &element.attrs {
+            match attr {
+                Attribute::Class { .. } => {}
+                Attribute::Id { name, .. } => attrs.push(Attr {
+                    name: "id".to_string(),
+                    value: match name {
+                        HtmlNameOrMarkup::HtmlName(name) => Some(name.to_string()),
+                        HtmlNameOrMarkup::Markup(_) => None,
+                    },
+                    span: attr.span(),
+                }),
+                Attribute::Named { name, attr_type } => attrs.push(Attr {
+                    name: name.to_string().to_ascii_lowercase(),
+                    value: match attr_type {
+                        AttributeType::Normal {
+                            value: Markup::Lit(lit),
+                            ..
+                        } => Some(lit.to_string()),
+                        AttributeType::Empty(None) => Some(String::new()),
+                        _ => None,
+                    },
+                    span: name.span(),
+                }),
+                Attribute::Spread { .. } => has_spread = true,
+            }
+        }
+        Attrs { attrs, has_spread }
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns `Some` if the attribute is present, with its value if it's a
+    /// literal.
+    fn get(&self, name: &str) -> Option<Option<&String>> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| attr.value.as_ref())
+    }
+}
+
+/// Returns the span of the given lint name in an element's `#[allow(...)]`.
+fn lint_span(element: &Element, lint: &str) -> Span {
+    element
+        .allows
+        .iter()
+        .flat_map(|allow| &allow.lints)
+        .find(|ident| *ident == lint)
+        .map_or_else(|| element.span(), |ident| ident.span())
+}
+
+/// Returns the closest match for a misspelled name, if there's one that's
+/// close enough.
+fn suggest(name: &str, candidates: &[&'static str]) -> Option<&'static str> {
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn allowed_children(parent: &str) -> Option<&'static [&'static str]> {
+    Some(match parent {
+        "ul" | "ol" | "menu" => &["li", "script", "template"],
+        "dl" => &["dt", "dd", "div", "script", "template"],
+        "table" => &[
+            "caption", "colgroup", "thead", "tbody", "tfoot", "tr", "script", "template",
+        ],
+        "thead" | "tbody" | "tfoot" => &["tr", "script", "template"],
+        "tr" => &["td", "th", "script", "template"],
+        "colgroup" => &["col", "template"],
+        "select" => &["option", "optgroup", "hr", "script", "template"],
+        "optgroup" => &["option", "script", "template"],
+        "html" => &["head", "body"],
+        _ => return None,
+    })
+}
+
+/// Returns the edit distance between two strings, counting a swap of two
+/// adjacent characters as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Every element in the HTML standard, apart from obsolete ones.
+const ELEMENTS: &[&str] = &[
+    "a",
+    "abbr",
+    "address",
+    "area",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "base",
+    "bdi",
+    "bdo",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "canvas",
+    "caption",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "data",
+    "datalist",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "embed",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "i",
+    "iframe",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "label",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "map",
+    "mark",
+    "math",
+    "menu",
+    "meta",
+    "meter",
+    "nav",
+    "noscript",
+    "object",
+    "ol",
+    "optgroup",
+    "option",
+    "output",
+    "p",
+    "picture",
+    "pre",
+    "progress",
+    "q",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "script",
+    "search",
+    "section",
+    "select",
+    "slot",
+    "small",
+    "source",
+    "span",
+    "strong",
+    "style",
+    "sub",
+    "summary",
+    "sup",
+    "svg",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "textarea",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "title",
+    "tr",
+    "track",
+    "u",
+    "ul",
+    "var",
+    "video",
+    "wbr",
+];
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements that can only contain phrasing content (text and inline elements).
+const PHRASING_PARENTS: &[&str] = &[
+    "abbr", "b", "bdi", "bdo", "button", "cite", "code", "data", "dfn", "em", "h1", "h2", "h3",
+    "h4", "h5", "h6", "i", "kbd", "label", "mark", "meter", "output", "p", "pre", "progress", "q",
+    "s", "samp", "small", "span", "strong", "sub", "sup", "time", "u", "var",
+];
+
+/// Elements that aren't phrasing content, so can't be used in the elements
+/// above.
+const FLOW_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "caption",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+/// Elements whose content model is that of their parent.
+const TRANSPARENT_ELEMENTS: &[&str] = &[
+    "a", "audio", "canvas", "del", "ins", "map", "noscript", "object", "slot", "video",
+];
+
+/// Groups of elements that can't be nested inside each other.
+const NO_NESTING: &[&[&str]] = &[&["a", "button"], &["form"], &["label"]];
+
+/// The attributes in WAI-ARIA 1.2.
+const ARIA_ATTRIBUTES: &[&str] = &[
+    "aria-activedescendant",
+    "aria-atomic",
+    "aria-autocomplete",
+    "aria-braillelabel",
+    "aria-brailleroledescription",
+    "aria-busy",
+    "aria-checked",
+    "aria-colcount",
+    "aria-colindex",
+    "aria-colindextext",
+    "aria-colspan",
+    "aria-controls",
+    "aria-current",
+    "aria-describedby",
+    "aria-description",
+    "aria-details",
+    "aria-disabled",
+    "aria-dropeffect",
+    "aria-errormessage",
+    "aria-expanded",
+    "aria-flowto",
+    "aria-grabbed",
+    "aria-haspopup",
+    "aria-hidden",
+    "aria-invalid",
+    "aria-keyshortcuts",
+    "aria-label",
+    "aria-labelledby",
+    "aria-level",
+    "aria-live",
+    "aria-modal",
+    "aria-multiline",
+    "aria-multiselectable",
+    "aria-orientation",
+    "aria-owns",
+    "aria-placeholder",
+    "aria-posinset",
+    "aria-pressed",
+    "aria-readonly",
+    "aria-relevant",
+    "aria-required",
+    "aria-roledescription",
+    "aria-rowcount",
+    "aria-rowindex",
+    "aria-rowindextext",
+    "aria-rowspan",
+    "aria-selected",
+    "aria-setsize",
+    "aria-sort",
+    "aria-valuemax",
+    "aria-valuemin",
+    "aria-valuenow",
+    "aria-valuetext",
+];
+
+/// The non-abstract roles in WAI-ARIA 1.2, and `none`/`presentation`.
+const ROLES: &[&str] = &[
+    "alert",
+    "alertdialog",
+    "application",
+    "article",
+    "banner",
+    "blockquote",
+    "button",
+    "caption",
+    "cell",
+    "checkbox",
+    "code",
+    "columnheader",
+    "combobox",
+    "complementary",
+    "contentinfo",
+    "definition",
+    "deletion",
+    "dialog",
+    "directory",
+    "document",
+    "emphasis",
+    "feed",
+    "figure",
+    "form",
+    "generic",
+    "grid",
+    "gridcell",
+    "group",
+    "heading",
+    "img",
+    "insertion",
+    "link",
+    "list",
+    "listbox",
+    "listitem",
+    "log",
+    "main",
+    "marquee",
+    "math",
+    "menu",
+    "menubar",
+    "menuitem",
+    "menuitemcheckbox",
+    "menuitemradio",
+    "meter",
+    "navigation",
+    "none",
+    "note",
+    "option",
+    "paragraph",
+    "presentation",
+    "progressbar",
+    "radio",
+    "radiogroup",
+    "region",
+    "row",
+    "rowgroup",
+    "rowheader",
+    "scrollbar",
+    "search",
+    "searchbox",
+    "separator",
+    "slider",
+    "spinbutton",
+    "status",
+    "strong",
+    "subscript",
+    "superscript",
+    "switch",
+    "tab",
+    "table",
+    "tablist",
+    "tabpanel",
+    "term",
+    "textbox",
+    "time",
+    "timer",
+    "toolbar",
+    "tooltip",
+    "tree",
+    "treegrid",
+    "treeitem",
+];
+
+#[cfg(test)]
+mod test {
+    use proc_macro2_diagnostics::Diagnostic;
+    use syn::parse::Parser;
+
+    use maud_syntax::{DiagnosticParse, Markups};
+
+    fn lint(input: &str) -> Vec<String> {
+        let mut diagnostics = Vec::new();
+        let markups = Parser::parse_str(
+            |input: syn::parse::ParseStream| Markups::diagnostic_parse(input, &mut Vec::new()),
+            input,
+        )
+        .unwrap();
+        super::lint(&markups, &mut diagnostics);
+        diagnostics
+            .into_iter()
+            .map(|diagnostic: Diagnostic| syn::Error::from(diagnostic).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn valid() {
+        assert!(
+            lint(
+                r#"
+                    html {
+                        head { meta charset="utf-8"; title { "Hi" } }
+                        body {
+                            .card { p { span { "a" } br; a href="/" { "b" } } }
+                            ul { @for i in 0..3 { li { (i) } } }
+                            table { tr { td { "c" } } }
+                            my-widget { div {} }
+                            svg { circle r="1"; }
+                        }
+                    }
+                "#
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn unknown_element() {
+        let warnings = lint("dvi { } blink { }");
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("unknown element `dvi`"));
+        assert!(warnings[0].contains("did you mean `div`?"));
+        assert!(warnings[1].contains("unknown element `blink`"));
+    }
+
+    #[test]
+    fn void_elements() {
+        let warnings = lint("br { } img src=\"a.png\" alt=\"\" { } div; my-element;");
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("`br` is a void element"));
+        assert!(warnings[1].contains("`img` is a void element"));
+        assert!(warnings[2].contains("`div` is not a void element"));
+    }
+
+    #[test]
+    fn nesting() {
+        let warnings = lint(
+            r#"
+                p { p { } }
+                span { @if true { div { } } }
+                span { a href="/" { ul { } } }
+                ul { div { } }
+                a href="/" { button { } }
+                form { .row { form { } } }
+            "#,
+        );
+        assert_eq!(warnings.len(), 6, "{warnings:#?}");
+        assert!(warnings[0].contains("`p` can't be used inside `p`"));
+        assert!(warnings[1].contains("`div` can't be used inside `span`"));
+        assert!(warnings[2].contains("`ul` can't be used inside `span`"));
+        assert!(warnings[3].contains("`div` can't be used inside `ul`"));
+        assert!(warnings[4].contains("`button` can't be used inside another `a`"));
+        assert!(warnings[5].contains("`form` can't be used inside another `form`"));
+    }
+
+    #[test]
+    fn components_reset_parent() {
+        assert!(lint("span { @Card { div { } } }").is_empty());
+    }
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(super::edit_distance("div", "div"), 0);
+        assert_eq!(super::edit_distance("dvi", "div"), 1);
+        assert_eq!(super::edit_distance("sapn", "span"), 1);
+        assert_eq!(super::edit_distance("secton", "section"), 1);
+        assert_eq!(super::edit_distance("tabel", "label"), 1);
+    }
+
+    #[test]
+    fn accessibility() {
+        let warnings = lint(
+            r#"
+                img src="a.png";
+                a { "Click me" }
+                input type="text";
+                div aria-lable="Close" role="buton" {}
+            "#,
+        );
+        assert_eq!(warnings.len(), 5, "{warnings:#?}");
+        assert!(warnings[0].contains("`img` is missing an `alt` attribute"));
+        assert!(warnings[1].contains("`a` is missing an `href` attribute"));
+        assert!(warnings[2].contains("`input` has no label"));
+        assert!(warnings[3].contains("unknown ARIA attribute `aria-lable`"));
+        assert!(warnings[3].contains("did you mean `aria-label`?"));
+        assert!(warnings[4].contains("invalid role `buton`"));
+        assert!(warnings[4].contains("did you mean `button`?"));
+    }
+
+    #[test]
+    fn labels() {
+        let warnings = lint(
+            r#"
+                label { "Name" input type="text"; }
+                input #email type="email";
+                label for="email" { "Email" }
+                input type="search" aria-label="Search";
+                input type="hidden" name="token";
+                input #phone type="tel";
+                input #(dynamic) type="text";
+            "#,
+        );
+        assert_eq!(warnings.len(), 1, "{warnings:#?}");
+        assert!(warnings[0].contains("`input` has no label"));
+    }
+
+    #[test]
+    fn allow() {
+        let warnings = lint(
+            r#"
+                #[allow(missing_alt)] img src="a.png";
+                #[allow(invalid_nesting, unknown_element)]
+                span { div { blink {} } }
+                #[allow(missing_alt)] a { "Oops" }
+                #[allow(missing_alt)] .spread ..(attrs) { img ..(attrs); }
+                #[allow(typo)] br;
+            "#,
+        );
+        assert_eq!(warnings.len(), 2, "{warnings:#?}");
+        assert!(warnings[0].contains("`a` is missing an `href` attribute"));
+        assert!(warnings[1].contains("unknown lint `typo`"));
+    }
+}
@@ -0,0 +1,41 @@
+//! Warnings that work on stable Rust.
+//!
+//! A procedural macro can only emit a real warning on nightly Rust. Anywhere
+//! else, `proc_macro2_diagnostics` turns it into a `compile_error!`, which
+//! would fail the build of every crate that uses `html!`, including crates
+//! that merely depend on one with the `lint` feature enabled.
+//!
+//! Instead, each warning is emitted as a use of a deprecated constant, which
+//! `rustc` reports as a `deprecated` warning with the message attached. Like
+//! any other warning, Cargo hides it when building a dependency, and
+//! `-D warnings` makes it an error.
+
+use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2_diagnostics::{Diagnostic, Level};
+use quote::quote;
+
+/// Returns the tokens that report a diagnostic, in expression position.
+pub fn emit(diagnostic: Diagnostic) -> TokenStream {
+    if diagnostic.level() != Level::Warning {
+        return diagnostic.emit_as_expr_tokens();
+    }
+
+    // A diagnostic with several spans becomes several errors; report them
+    // all at the first one
+    let error = syn::Error::from(diagnostic);
+    let span = error.span();
+    let message = error
+        .into_iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let message = message.strip_prefix("[warning] ").unwrap_or(&message);
+
+    let name = Ident::new("html", span.resolved_at(Span::call_site()));
+    quote! {{
+        #[deprecated(note = #message)]
+        #[allow(non_upper_case_globals)]
+        const #name: () = ();
+        let _ = #name;
+    }}
+}
@@ -1,21 +1,62 @@
+//! The parser behind Maud's `html!` macro.
+//!
+//! This crate is for tools that work with Maud templates, such as
+//! formatters, linters and editor plugins. If you only want to write
+//! templates, use the [`maud`](https://docs.rs/maud/) crate instead.
+//!
+//! Templates are parsed from a [`TokenStream`] using the [`DiagnosticParse`]
+//! trait. Most mistakes don't stop the parser, but are added to a list of
+//! [`Diagnostic`]s instead, so that as many as possible are reported at once:
+//!
+//! ```rust
+//! use maud_syntax::{DiagnosticParse, Element, Markup, Markups};
+//! use syn::parse::{ParseStream, Parser};
+//!
+//! let tokens = r#"p.greeting { "Hello, " (name) "!" }"#.parse().unwrap();
+//! let mut diagnostics = Vec::new();
+//! let markups = Parser::parse2(
+//!     |input: ParseStream| Markups::<Element>::diagnostic_parse(input, &mut diagnostics),
+//!     tokens,
+//! )
+//! .unwrap();
+//! assert!(diagnostics.is_empty());
+//!
+//! let [Markup::Element(element)] = markups.markups.as_slice() else {
+//!     panic!("expected a single element");
+//! };
+//! assert_eq!(element.name.as_ref().unwrap().to_string(), "p");
+//! ```
+//!
+//! Every node implements [`ToTokens`], which writes it back out as it was
+//! written. This means that [`Spanned::span`]
+//! works on every node too.
+//!
+//! Many nodes take a type parameter `E`, which is [`Element`] where elements
+//! are allowed, and [`NoElement`] inside attribute values, where they aren't.
+
+#![doc(html_root_url = "https://docs.rs/maud_syntax/0.27.0")]
+#![allow(clippy::large_enum_variant)]
+
 use std::fmt::{self, Display, Formatter};
 
 use proc_macro2::TokenStream;
-use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
+pub use proc_macro2_diagnostics::Diagnostic;
+use proc_macro2_diagnostics::SpanDiagnosticExt;
 use quote::ToTokens;
 use syn::{
-    Error, Expr, Ident, Lit, LitBool, LitInt, LitStr, Local, Pat, Stmt, braced, bracketed,
+    Error, Expr, Ident, Lit, LitBool, LitInt, LitStr, Local, Pat, Path, Stmt, braced, bracketed,
     ext::IdentExt,
     parenthesized,
     parse::{Lookahead1, Parse, ParseStream},
     punctuated::{Pair, Punctuated},
     spanned::Spanned,
     token::{
-        At, Brace, Bracket, Colon, Comma, Dot, Else, Eq, FatArrow, For, If, In, Let, Match, Minus,
-        Paren, Pound, Question, Semi, Slash, While,
+        At, Brace, Bracket, Colon, Comma, Dot, DotDot, Else, Eq, FatArrow, For, If, In, Let, Match,
+        Minus, Paren, Pound, Question, Semi, Slash, While,
     },
 };
 
+/// A sequence of markups, such as the body of a macro or a block.
 #[derive(Debug, Clone)]
 pub struct Markups<E> {
     pub markups: Vec<Markup<E>>,
@@ -42,17 +83,35 @@ impl<E: ToTokens> ToTokens for Markups<E> {
     }
 }
 
+/// A single item in a template.
 #[derive(Debug, Clone)]
 pub enum Markup<E> {
+    /// A block, as in `{ ... }`.
     Block(Block<E>),
+    /// A string literal, as in `"Hello"`.
     Lit(HtmlLit),
-    Splice { paren_token: Paren, expr: Expr },
+    /// A Rust expression, as in `(name)`, with an optional `#[escape(...)]`
+    /// attribute.
+    Splice {
+        escape: Option<Escape>,
+        paren_token: Paren,
+        expr: Expr,
+    },
+    /// An element, where one is allowed.
     Element(E),
+    /// A control structure, as in `@if` or `@for`.
     ControlFlow(ControlFlow<E>),
+    /// A component invocation, as in `@Card { ... }`.
+    Component(Component),
+    /// A stray `;`, which doesn't do anything.
     Semi(Semi),
 }
 
 impl<E: MaybeElement> Markup<E> {
+    /// Parses a markup inside a block, where `@let` is allowed.
+    ///
+    /// [`DiagnosticParse::diagnostic_parse`] reports an error for `@let`
+    /// instead.
     pub fn diagnostic_parse_in_block(
         input: ParseStream,
         diagnostics: &mut Vec<Diagnostic>,
@@ -72,6 +131,16 @@ impl<E: MaybeElement> Markup<E> {
             );
         }
 
+        let escape = if Escape::peek(input) {
+            let escape = input.diagnostic_parse(diagnostics)?;
+            if !input.peek(Paren) {
+                return Err(input.error("expected a splice after `#[escape(...)]`"));
+            }
+            Some(escape)
+        } else {
+            None
+        };
+
         let lookahead = input.lookahead1();
 
         if lookahead.peek(Brace) {
@@ -81,13 +150,28 @@ impl<E: MaybeElement> Markup<E> {
         } else if lookahead.peek(Paren) {
             let content;
             Ok(Self::Splice {
+                escape,
                 paren_token: parenthesized!(content in input),
                 expr: content.parse()?,
             })
         } else if let Some(parse_element) = E::should_parse(&lookahead) {
             parse_element(input, diagnostics).map(Self::Element)
         } else if lookahead.peek(At) {
-            input.diagnostic_parse(diagnostics).map(Self::ControlFlow)
+            // Control flow keywords aren't identifiers, so anything else is a
+            // component
+            if input.peek2(Ident) {
+                let component = input.diagnostic_parse::<Component>(diagnostics)?;
+                if !E::allows_components() {
+                    diagnostics.push(
+                        component
+                            .span()
+                            .error("components are not allowed in attributes"),
+                    );
+                }
+                Ok(Self::Component(component))
+            } else {
+                input.diagnostic_parse(diagnostics).map(Self::ControlFlow)
+            }
         } else if lookahead.peek(Semi) {
             input.parse().map(Self::Semi)
         } else {
@@ -124,18 +208,99 @@ impl<E: ToTokens> ToTokens for Markup<E> {
         match self {
             Self::Block(block) => block.to_tokens(tokens),
             Self::Lit(lit) => lit.to_tokens(tokens),
-            Self::Splice { paren_token, expr } => {
+            Self::Splice {
+                escape,
+                paren_token,
+                expr,
+            } => {
+                escape.to_tokens(tokens);
                 paren_token.surround(tokens, |tokens| {
                     expr.to_tokens(tokens);
                 });
             }
             Self::Element(element) => element.to_tokens(tokens),
             Self::ControlFlow(control_flow) => control_flow.to_tokens(tokens),
+            Self::Component(component) => component.to_tokens(tokens),
             Self::Semi(semi) => semi.to_tokens(tokens),
         }
     }
 }
 
+/// An `#[escape(...)]` attribute, which chooses the characters to escape in a
+/// splice.
+#[derive(Debug, Clone)]
+pub struct Escape {
+    pub pound_token: Pound,
+    pub bracket_token: Bracket,
+    pub escape_token: Ident,
+    pub paren_token: Paren,
+    /// The name of the policy, which is one of [`Escape::POLICIES`].
+    pub policy: Ident,
+}
+
+impl Escape {
+    /// The names of the escaping policies, which match the variants of
+    /// `maud::EscapePolicy`.
+    pub const POLICIES: &[&str] = &["text", "double_quoted_attr", "single_quoted_attr", "strict"];
+
+    /// Returns whether the input starts with an `#[escape(...)]` attribute,
+    /// rather than an `#[allow(...)]` or an ID.
+    fn peek(input: ParseStream) -> bool {
+        let fork = input.fork();
+        let is_escape = || -> syn::Result<bool> {
+            fork.parse::<Pound>()?;
+            let content;
+            bracketed!(content in fork);
+            Ok(content.call(Ident::parse_any)? == "escape")
+        };
+        input.peek(Pound) && input.peek2(Bracket) && is_escape().unwrap_or(false)
+    }
+}
+
+impl DiagnosticParse for Escape {
+    fn diagnostic_parse(input: ParseStream, _: &mut Vec<Diagnostic>) -> syn::Result<Self> {
+        let pound_token = input.parse()?;
+        let content;
+        let bracket_token = bracketed!(content in input);
+        let escape_token = content.call(Ident::parse_any)?;
+        let policy_content;
+        let paren_token = parenthesized!(policy_content in content);
+        let policy = policy_content.call(Ident::parse_any)?;
+        if !Self::POLICIES.iter().any(|name| policy == name) {
+            return Err(Error::new_spanned(
+                &policy,
+                format!(
+                    "unknown escaping policy `{policy}`; expected one of {}",
+                    Self::POLICIES
+                        .iter()
+                        .map(|name| format!("`{name}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+        }
+        Ok(Self {
+            pound_token,
+            bracket_token,
+            escape_token,
+            paren_token,
+            policy,
+        })
+    }
+}
+
+impl ToTokens for Escape {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.pound_token.to_tokens(tokens);
+        self.bracket_token.surround(tokens, |tokens| {
+            self.escape_token.to_tokens(tokens);
+            self.paren_token.surround(tokens, |tokens| {
+                self.policy.to_tokens(tokens);
+            });
+        });
+    }
+}
+
 /// Represents a context that may or may not allow elements.
 ///
 /// An attribute accepts almost the same syntax as an element body, except child elements aren't
@@ -144,6 +309,9 @@ impl<E: ToTokens> ToTokens for Markup<E> {
 pub trait MaybeElement: Sized + ToTokens {
     /// If an element can be parsed here, returns `Some` with a parser for the rest of the element.
     fn should_parse(lookahead: &Lookahead1<'_>) -> Option<DiagnosticParseFn<Self>>;
+
+    /// Whether a component can be used here.
+    fn allows_components() -> bool;
 }
 
 /// An implementation of `DiagnosticParse::diagnostic_parse`.
@@ -159,6 +327,10 @@ impl MaybeElement for NoElement {
     ) -> Option<fn(ParseStream, &mut Vec<Diagnostic>) -> syn::Result<Self>> {
         None
     }
+
+    fn allows_components() -> bool {
+        false
+    }
 }
 
 impl ToTokens for NoElement {
@@ -167,8 +339,11 @@ impl ToTokens for NoElement {
     }
 }
 
+/// An HTML element, as in `p.intro { ... }`. The name is left out when it's
+/// a `div` with a class or ID, as in `.card { ... }`.
 #[derive(Debug, Clone)]
 pub struct Element {
+    pub allows: Vec<Allow>,
     pub name: Option<HtmlName>,
     pub attrs: Vec<Attribute>,
     pub body: ElementBody,
@@ -190,6 +365,10 @@ impl MaybeElement for Element {
             None
         }
     }
+
+    fn allows_components() -> bool {
+        true
+    }
 }
 
 impl DiagnosticParse for Element {
@@ -198,6 +377,20 @@ impl DiagnosticParse for Element {
         diagnostics: &mut Vec<Diagnostic>,
     ) -> syn::Result<Self> {
         Ok(Self {
+            allows: {
+                let mut allows = Vec::new();
+                while input.peek(Pound) && input.peek2(Bracket) {
+                    allows.push(input.diagnostic_parse(diagnostics)?);
+                }
+                if !allows.is_empty()
+                    && !input.peek(Ident::peek_any)
+                    && !input.peek(Dot)
+                    && !input.peek(Pound)
+                {
+                    return Err(input.error("expected an element after `#[allow(...)]`"));
+                }
+                allows
+            },
             name: if input.peek(Ident::peek_any) {
                 Some(input.diagnostic_parse(diagnostics)?)
             } else {
@@ -206,6 +399,7 @@ impl DiagnosticParse for Element {
             attrs: {
                 let mut id_pushed = false;
                 let mut attrs = Vec::new();
+                let mut names = Vec::new();
 
                 while input.peek(Ident::peek_any)
                     || input.peek(Lit)
@@ -224,14 +418,40 @@ impl DiagnosticParse for Element {
                         id_pushed = true;
                     }
 
+                    if let Some(name) = attr.static_name() {
+                        if names.contains(&name) {
+                            diagnostics
+                                .push(attr.span().error(format!("duplicate attribute `{name}`")));
+                        }
+                        names.push(name);
+                    }
+
                     attrs.push(attr);
                 }
 
+                let has_class_shorthand = attrs
+                    .iter()
+                    .any(|attr| matches!(attr, Attribute::Class { .. }));
+                for attr in &attrs {
+                    if let Attribute::Named { name, attr_type } = attr
+                        && has_class_shorthand
+                        && name.to_string().eq_ignore_ascii_case("class")
+                        && !matches!(attr_type, AttributeType::Normal { .. })
+                    {
+                        diagnostics.push(
+                            attr.span()
+                                .error("this `class` attribute can't be combined with `.class` shorthands")
+                                .help("only `class=\"...\"` can be combined with them"),
+                        );
+                    }
+                }
+
                 if !(input.peek(Brace) || input.peek(Semi) || input.peek(Slash)) {
                     let lookahead = input.lookahead1();
 
                     lookahead.peek(Ident::peek_any);
                     lookahead.peek(Lit);
+                    lookahead.peek(DotDot);
                     lookahead.peek(Dot);
                     lookahead.peek(Pound);
 
@@ -250,6 +470,9 @@ impl DiagnosticParse for Element {
 
 impl ToTokens for Element {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        for allow in &self.allows {
+            allow.to_tokens(tokens);
+        }
         if let Some(name) = &self.name {
             name.to_tokens(tokens);
         }
@@ -260,6 +483,53 @@ impl ToTokens for Element {
     }
 }
 
+/// An `#[allow(...)]` attribute, which silences lints on an element and its
+/// children.
+#[derive(Debug, Clone)]
+pub struct Allow {
+    pub pound_token: Pound,
+    pub bracket_token: Bracket,
+    pub allow_token: Ident,
+    pub paren_token: Paren,
+    pub lints: Punctuated<Ident, Comma>,
+}
+
+impl DiagnosticParse for Allow {
+    fn diagnostic_parse(input: ParseStream, _: &mut Vec<Diagnostic>) -> syn::Result<Self> {
+        let pound_token = input.parse()?;
+        let content;
+        let bracket_token = bracketed!(content in input);
+        let allow_token = content.call(Ident::parse_any)?;
+        if allow_token != "allow" {
+            return Err(Error::new_spanned(
+                allow_token,
+                "only `#[allow(...)]` can be used on an element",
+            ));
+        }
+        let lints;
+        Ok(Self {
+            pound_token,
+            bracket_token,
+            allow_token,
+            paren_token: parenthesized!(lints in content),
+            lints: lints.parse_terminated(Ident::parse_any, Comma)?,
+        })
+    }
+}
+
+impl ToTokens for Allow {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.pound_token.to_tokens(tokens);
+        self.bracket_token.surround(tokens, |tokens| {
+            self.allow_token.to_tokens(tokens);
+            self.paren_token.surround(tokens, |tokens| {
+                self.lints.to_tokens(tokens);
+            });
+        });
+    }
+}
+
+/// The body of an element: either a `;` for a void element, or a block.
 #[derive(Debug, Clone)]
 pub enum ElementBody {
     Void(Semi),
@@ -303,6 +573,7 @@ impl ToTokens for ElementBody {
     }
 }
 
+/// A sequence of markups in braces.
 #[derive(Debug, Clone)]
 pub struct Block<E> {
     pub brace_token: Brace,
@@ -330,21 +601,273 @@ impl<E: ToTokens> ToTokens for Block<E> {
     }
 }
 
+/// A component invocation, as in `@Card title="Hi" { ... }`.
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub at_token: At,
+    pub path: Path,
+    pub props: Vec<Prop>,
+    pub body: ComponentBody,
+}
+
+impl DiagnosticParse for Component {
+    fn diagnostic_parse(
+        input: ParseStream,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> syn::Result<Self> {
+        let at_token = input.parse()?;
+        let path = input.call(Path::parse_mod_style)?;
+
+        if path.is_ident("slot") {
+            return Err(Error::new_spanned(
+                path,
+                "`@slot` is only allowed directly inside a component",
+            ));
+        }
+
+        let mut props = Vec::new();
+        while input.peek(Ident) {
+            props.push(input.diagnostic_parse(diagnostics)?);
+        }
+
+        Ok(Self {
+            at_token,
+            path,
+            props,
+            body: input.diagnostic_parse(diagnostics)?,
+        })
+    }
+}
+
+impl ToTokens for Component {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.at_token.to_tokens(tokens);
+        self.path.to_tokens(tokens);
+        for prop in &self.props {
+            prop.to_tokens(tokens);
+        }
+        self.body.to_tokens(tokens);
+    }
+}
+
+/// A property passed to a component, as in `title="Hi"`.
+#[derive(Debug, Clone)]
+pub struct Prop {
+    pub name: Ident,
+    pub eq_token: Eq,
+    pub value: PropValue,
+}
+
+impl DiagnosticParse for Prop {
+    fn diagnostic_parse(input: ParseStream, _: &mut Vec<Diagnostic>) -> syn::Result<Self> {
+        Ok(Self {
+            name: input.parse()?,
+            eq_token: input.parse()?,
+            value: {
+                let lookahead = input.lookahead1();
+
+                if lookahead.peek(Lit) {
+                    PropValue::Lit(input.parse()?)
+                } else if lookahead.peek(Paren) {
+                    let content;
+                    PropValue::Splice {
+                        paren_token: parenthesized!(content in input),
+                        expr: content.parse()?,
+                    }
+                } else {
+                    return Err(lookahead.error());
+                }
+            },
+        })
+    }
+}
+
+impl ToTokens for Prop {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.name.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+/// The value of a component property.
+#[derive(Debug, Clone)]
+pub enum PropValue {
+    Lit(Lit),
+    Splice { paren_token: Paren, expr: Expr },
+}
+
+impl ToTokens for PropValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Lit(lit) => lit.to_tokens(tokens),
+            Self::Splice { paren_token, expr } => {
+                paren_token.surround(tokens, |tokens| {
+                    expr.to_tokens(tokens);
+                });
+            }
+        }
+    }
+}
+
+/// The body of a component invocation: either a `;`, or its children in
+/// braces.
+#[derive(Debug, Clone)]
+pub enum ComponentBody {
+    Void(Semi),
+    Block {
+        brace_token: Brace,
+        children: Vec<ComponentChild>,
+    },
+}
+
+impl DiagnosticParse for ComponentBody {
+    fn diagnostic_parse(
+        input: ParseStream,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+
+        if lookahead.peek(Semi) {
+            input.parse().map(Self::Void)
+        } else if lookahead.peek(Brace) {
+            let content;
+            let brace_token = braced!(content in input);
+
+            let mut children = Vec::new();
+            while !content.is_empty() {
+                children.push(content.diagnostic_parse(diagnostics)?);
+            }
+
+            let mut slot_names = Vec::new();
+            for child in &children {
+                if let ComponentChild::Slot(slot) = child {
+                    if slot_names.contains(&&slot.name) {
+                        diagnostics.push(
+                            slot.name
+                                .span()
+                                .error(format!("duplicate slot `{}`", slot.name)),
+                        );
+                    }
+                    slot_names.push(&slot.name);
+                }
+            }
+
+            Ok(Self::Block {
+                brace_token,
+                children,
+            })
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for ComponentBody {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Void(semi) => semi.to_tokens(tokens),
+            Self::Block {
+                brace_token,
+                children,
+            } => {
+                brace_token.surround(tokens, |tokens| {
+                    for child in children {
+                        child.to_tokens(tokens);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// A child of a component, which can be a slot as well as regular markup.
+#[derive(Debug, Clone)]
+pub enum ComponentChild {
+    Markup(Markup<Element>),
+    Slot(Slot),
+}
+
+impl DiagnosticParse for ComponentChild {
+    fn diagnostic_parse(
+        input: ParseStream,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> syn::Result<Self> {
+        let fork = input.fork();
+        if fork.parse::<At>().is_ok() && fork.call(Ident::parse_any).is_ok_and(|kw| kw == "slot") {
+            input.diagnostic_parse(diagnostics).map(Self::Slot)
+        } else {
+            Markup::diagnostic_parse_in_block(input, diagnostics).map(Self::Markup)
+        }
+    }
+}
+
+impl ToTokens for ComponentChild {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Markup(markup) => markup.to_tokens(tokens),
+            Self::Slot(slot) => slot.to_tokens(tokens),
+        }
+    }
+}
+
+/// A named slot in a component body, as in `@slot footer { ... }`.
+#[derive(Debug, Clone)]
+pub struct Slot {
+    pub at_token: At,
+    pub slot_token: Ident,
+    pub name: Ident,
+    pub body: Block<Element>,
+}
+
+impl DiagnosticParse for Slot {
+    fn diagnostic_parse(
+        input: ParseStream,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> syn::Result<Self> {
+        Ok(Self {
+            at_token: input.parse()?,
+            slot_token: input.call(Ident::parse_any)?,
+            name: input.call(Ident::parse_any)?,
+            body: input.diagnostic_parse(diagnostics)?,
+        })
+    }
+}
+
+impl ToTokens for Slot {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.at_token.to_tokens(tokens);
+        self.slot_token.to_tokens(tokens);
+        self.name.to_tokens(tokens);
+        self.body.to_tokens(tokens);
+    }
+}
+
+/// An attribute on an element.
 #[derive(Debug, Clone)]
 pub enum Attribute {
+    /// A class shorthand, as in `.big` or `.big[is_big]`.
     Class {
         dot_token: Dot,
         name: HtmlNameOrMarkup,
         toggler: Option<Toggler>,
     },
+    /// An ID shorthand, as in `#main`.
     Id {
         pound_token: Pound,
         name: HtmlNameOrMarkup,
     },
+    /// A named attribute, as in `href="/"` or `checked[done]`.
     Named {
         name: HtmlName,
         attr_type: AttributeType,
     },
+    /// Attributes whose names are only known at runtime, as in `..(attrs)`.
+    Spread {
+        dot2_token: DotDot,
+        paren_token: Paren,
+        expr: Expr,
+    },
 }
 
 impl DiagnosticParse for Attribute {
@@ -354,7 +877,14 @@ impl DiagnosticParse for Attribute {
     ) -> syn::Result<Self> {
         let lookahead = input.lookahead1();
 
-        if lookahead.peek(Dot) {
+        if lookahead.peek(DotDot) {
+            let content;
+            Ok(Self::Spread {
+                dot2_token: input.parse()?,
+                paren_token: parenthesized!(content in input),
+                expr: content.parse()?,
+            })
+        } else if lookahead.peek(Dot) {
             Ok(Self::Class {
                 dot_token: input.parse()?,
                 name: input.diagnostic_parse(diagnostics)?,
@@ -401,6 +931,21 @@ impl DiagnosticParse for Attribute {
     }
 }
 
+impl Attribute {
+    /// Returns the lowercased name of this attribute, if it's known at compile
+    /// time and can't be merged with others.
+    ///
+    /// `.class` shorthands are merged into a single attribute, so they don't
+    /// count.
+    fn static_name(&self) -> Option<String> {
+        match self {
+            Self::Id { .. } => Some("id".to_string()),
+            Self::Named { name, .. } => Some(name.to_string().to_ascii_lowercase()),
+            Self::Class { .. } | Self::Spread { .. } => None,
+        }
+    }
+}
+
 impl ToTokens for Attribute {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
@@ -423,10 +968,21 @@ impl ToTokens for Attribute {
                 name.to_tokens(tokens);
                 attr_type.to_tokens(tokens);
             }
+            Self::Spread {
+                dot2_token,
+                paren_token,
+                expr,
+            } => {
+                dot2_token.to_tokens(tokens);
+                paren_token.surround(tokens, |tokens| {
+                    expr.to_tokens(tokens);
+                });
+            }
         }
     }
 }
 
+/// The name in an `.class` or `#id` shorthand, which can be spliced in.
 #[derive(Debug, Clone)]
 pub enum HtmlNameOrMarkup {
     HtmlName(HtmlName),
@@ -470,16 +1026,17 @@ impl Display for HtmlNameOrMarkup {
     }
 }
 
+/// The value of a named attribute, if any.
 #[derive(Debug, Clone)]
 pub enum AttributeType {
+    /// A value, as in `href="/"`.
     Normal {
         eq_token: Eq,
         value: Markup<NoElement>,
     },
-    Optional {
-        eq_token: Eq,
-        toggler: Toggler,
-    },
+    /// A value that's only added if it's `Some`, as in `title=[title]`.
+    Optional { eq_token: Eq, toggler: Toggler },
+    /// No value, as in `checked` or `checked[done]`.
     Empty(Option<Toggler>),
 }
 
@@ -532,6 +1089,7 @@ impl ToTokens for AttributeType {
     }
 }
 
+/// An HTML element or attribute name, as in `data-value` or `xml:lang`.
 #[derive(Debug, Clone)]
 pub struct HtmlName {
     pub name: Punctuated<HtmlNameFragment, HtmlNamePunct>,
@@ -593,6 +1151,7 @@ impl Display for HtmlName {
     }
 }
 
+/// A part of an HTML name, between the `-` and `:` separators.
 #[derive(Debug, Clone)]
 pub enum HtmlNameFragment {
     Ident(Ident),
@@ -644,6 +1203,7 @@ impl Display for HtmlNameFragment {
     }
 }
 
+/// A string literal, written as text.
 #[derive(Debug, Clone)]
 pub struct HtmlLit {
     pub lit: LitStr,
@@ -718,6 +1278,7 @@ impl Display for HtmlLit {
     }
 }
 
+/// A separator in an HTML name.
 #[derive(Debug, Clone)]
 pub enum HtmlNamePunct {
     Colon(Colon),
@@ -756,6 +1317,8 @@ impl Display for HtmlNamePunct {
     }
 }
 
+/// A condition in brackets, as in `checked[is_checked]`, which decides
+/// whether an attribute or class is added.
 #[derive(Debug, Clone)]
 pub struct Toggler {
     pub bracket_token: Bracket,
@@ -780,6 +1343,7 @@ impl ToTokens for Toggler {
     }
 }
 
+/// A control structure, such as `@if` or `@for`.
 #[derive(Debug, Clone)]
 pub struct ControlFlow<E> {
     pub at_token: At,
@@ -831,6 +1395,7 @@ impl<E: ToTokens> ToTokens for ControlFlow<E> {
     }
 }
 
+/// The kind of a control structure, after the `@`.
 #[derive(Debug, Clone)]
 pub enum ControlFlowKind<E> {
     Let(Local),
@@ -840,6 +1405,7 @@ pub enum ControlFlowKind<E> {
     Match(MatchExpr<E>),
 }
 
+/// An `@if` or `@if let`, with any `@else` branches.
 #[derive(Debug, Clone)]
 pub struct IfExpr<E> {
     pub if_token: If,
@@ -885,6 +1451,7 @@ impl<E: ToTokens> ToTokens for IfExpr<E> {
     }
 }
 
+/// What comes after `@else`: either another `if`, or a block.
 #[derive(Debug, Clone)]
 pub enum IfOrBlock<E> {
     If(IfExpr<E>),
@@ -917,6 +1484,7 @@ impl<E: ToTokens> ToTokens for IfOrBlock<E> {
     }
 }
 
+/// An `@for` loop.
 #[derive(Debug, Clone)]
 pub struct ForExpr<E> {
     pub for_token: For,
@@ -951,6 +1519,7 @@ impl<E: ToTokens> ToTokens for ForExpr<E> {
     }
 }
 
+/// An `@while` or `@while let` loop.
 #[derive(Debug, Clone)]
 pub struct WhileExpr<E> {
     pub while_token: While,
@@ -979,6 +1548,7 @@ impl<E: ToTokens> ToTokens for WhileExpr<E> {
     }
 }
 
+/// An `@match` expression.
 #[derive(Debug, Clone)]
 pub struct MatchExpr<E> {
     pub match_token: Match,
@@ -1024,6 +1594,7 @@ impl<E: ToTokens> ToTokens for MatchExpr<E> {
     }
 }
 
+/// An arm of an `@match` expression.
 #[derive(Debug, Clone)]
 pub struct MatchArm<E> {
     pub pat: Pat,
@@ -1073,6 +1644,9 @@ impl<E: ToTokens> ToTokens for MatchArm<E> {
     }
 }
 
+/// Parses a syntax tree, collecting errors that don't stop the parser.
+///
+/// An `Err` is only returned if parsing can't continue at all.
 pub trait DiagnosticParse: Sized {
     fn diagnostic_parse(input: ParseStream, diagnostics: &mut Vec<Diagnostic>)
     -> syn::Result<Self>;
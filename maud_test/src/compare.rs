@@ -0,0 +1,276 @@
+//! Comparing documents, and describing how they differ.
+
+use std::fmt::Write;
+
+use maud_escape::INLINE_ELEMENTS;
+
+use crate::parse::{Attr, Element, Node};
+
+/// Puts a document into a form where equivalent HTML compares equal.
+///
+/// Attributes are sorted by name, and the classes in a `class` attribute are
+/// sorted too. Runs of whitespace in text are collapsed into a single space,
+/// except inside elements like `pre` where they show up on the page. Next to
+/// a block element, or at the start or end of one, whitespace is removed
+/// entirely. Comments are removed.
+///
+/// `inline` is whether the nodes are inside an inline element, whose edges
+/// don't remove whitespace.
+pub fn normalize(nodes: Vec<Node>, preserve_whitespace: bool, inline: bool) -> Vec<Node> {
+    let mut normalized = Vec::<Node>::new();
+    for node in nodes {
+        match node {
+            Node::Element(element) => normalized.push(Node::Element(normalize_element(element))),
+            Node::Text(text) => {
+                // Removing a comment can leave two pieces of text next to
+                // each other
+                if let Some(Node::Text(previous)) = normalized.last_mut() {
+                    previous.push_str(&text);
+                } else {
+                    normalized.push(Node::Text(text));
+                }
+            }
+            Node::Comment(_) => {}
+            Node::Doctype(doctype) => {
+                normalized.push(Node::Doctype(collapse_whitespace(&doctype).to_lowercase()))
+            }
+        }
+    }
+    if !preserve_whitespace {
+        for i in 0..normalized.len() {
+            let is_inline = |node: Option<&Node>| match node {
+                Some(Node::Element(element)) => INLINE_ELEMENTS.contains(&element.name.as_str()),
+                Some(_) => false,
+                None => inline,
+            };
+            let trim_start = !is_inline(i.checked_sub(1).map(|i| &normalized[i]));
+            let trim_end = !is_inline(normalized.get(i + 1));
+            if let Node::Text(text) = &mut normalized[i] {
+                let mut collapsed = collapse_whitespace(text);
+                if trim_start {
+                    collapsed = collapsed.trim_start_matches(' ').to_string();
+                }
+                if trim_end {
+                    collapsed.truncate(collapsed.trim_end_matches(' ').len());
+                }
+                *text = collapsed;
+            }
+        }
+        normalized.retain(|node| !matches!(node, Node::Text(text) if text.is_empty()));
+    }
+    normalized
+}
+
+fn normalize_element(mut element: Element) -> Element {
+    for attr in &mut element.attrs {
+        if attr.name == "class"
+            && let Some(value) = &attr.value
+        {
+            let mut classes = value.split_ascii_whitespace().collect::<Vec<_>>();
+            classes.sort_unstable();
+            attr.value = Some(classes.join(" "));
+        }
+        // `disabled` is the same as `disabled=""`
+        if attr.value.as_deref() == Some("") {
+            attr.value = None;
+        }
+    }
+    element.attrs.sort_by(|a, b| a.name.cmp(&b.name));
+    let preserve = matches!(
+        element.name.as_str(),
+        "pre" | "textarea" | "script" | "style"
+    );
+    let inline = INLINE_ELEMENTS.contains(&element.name.as_str());
+    element.children = normalize(element.children, preserve, inline);
+    element
+}
+
+/// Replaces each run of whitespace with a single space.
+fn collapse_whitespace(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_whitespace {
+                output.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            output.push(c);
+            in_whitespace = false;
+        }
+    }
+    output
+}
+
+/// Finds the first place where two normalized documents differ.
+///
+/// Returns the path to the element that differs, or that contains the text
+/// that differs, as a list of CSS selectors. The path is empty if the
+/// difference is at the top level.
+pub fn first_difference(left: &[Node], right: &[Node]) -> Option<Vec<String>> {
+    for (i, (l, r)) in left.iter().zip(right).enumerate() {
+        if l == r {
+            continue;
+        }
+        let (Node::Element(l), Node::Element(r)) = (l, r) else {
+            return Some(Vec::new());
+        };
+        let mut path = vec![selector(left, i)];
+        if l.name == r.name
+            && l.attrs == r.attrs
+            && let Some(rest) = first_difference(&l.children, &r.children)
+        {
+            path.extend(rest);
+        }
+        return Some(path);
+    }
+    // One has nodes that the other doesn't
+    (left.len() != right.len()).then(Vec::new)
+}
+
+/// Returns a selector for the element at `index` among its siblings, such as
+/// `li:nth-of-type(2)`.
+fn selector(siblings: &[Node], index: usize) -> String {
+    let Node::Element(element) = &siblings[index] else {
+        unreachable!()
+    };
+    let same_name = |node: &&Node| matches!(node, Node::Element(e) if e.name == element.name);
+    if siblings.iter().filter(same_name).count() == 1 {
+        element.name.clone()
+    } else {
+        let n = siblings[..index].iter().filter(same_name).count() + 1;
+        format!("{}:nth-of-type({n})", element.name)
+    }
+}
+
+/// Writes a normalized document with one node per line, indented to show
+/// how they're nested.
+pub fn lines(nodes: &[Node]) -> Vec<String> {
+    let mut lines = Vec::new();
+    write_lines(nodes, 0, &mut lines);
+    lines
+}
+
+fn write_lines(nodes: &[Node], depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    for node in nodes {
+        match node {
+            Node::Element(element) => {
+                let mut line = format!("{indent}<{}", element.name);
+                for Attr { name, value } in &element.attrs {
+                    match value {
+                        Some(value) => {
+                            let _ = write!(line, " {name}={value:?}");
+                        }
+                        None => {
+                            let _ = write!(line, " {name}");
+                        }
+                    }
+                }
+                line.push('>');
+                lines.push(line);
+                write_lines(&element.children, depth + 1, lines);
+            }
+            Node::Text(text) => lines.push(format!("{indent}{text:?}")),
+            Node::Comment(comment) => lines.push(format!("{indent}<!--{comment}-->")),
+            Node::Doctype(doctype) => lines.push(format!("{indent}<!{doctype}>")),
+        }
+    }
+}
+
+/// Compares two lists of lines, marking lines only on the left with `-` and
+/// lines only on the right with `+`.
+pub fn diff(left: &[String], right: &[String]) -> String {
+    // The length of the longest common subsequence of `left[i..]` and
+    // `right[j..]`
+    let mut lengths = vec![vec![0; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lengths[i][j] = if left[i] == right[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i] == right[j] {
+            let _ = writeln!(output, " {}", left[i]);
+            i += 1;
+            j += 1;
+        } else if j == right.len() || (i < left.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            let _ = writeln!(output, "-{}", left[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(output, "+{}", right[j]);
+            j += 1;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff, first_difference, lines, normalize};
+    use crate::parse::parse;
+
+    fn normalized(html: &str) -> Vec<crate::parse::Node> {
+        normalize(parse(html), false, false)
+    }
+
+    #[test]
+    fn equivalent() {
+        assert_eq!(
+            normalized(r#"<p id="x" class="b  a">  Hello,<!-- hi -->  world </p>"#),
+            normalized(r#"<p class="a b" id="x">Hello, world</p>"#),
+        );
+        assert_eq!(
+            normalized("<input disabled=\"\">"),
+            normalized("<input disabled>"),
+        );
+        assert_ne!(normalized("<pre> a</pre>"), normalized("<pre>a</pre>"));
+    }
+
+    #[test]
+    fn inline_whitespace() {
+        assert_eq!(
+            normalized("<div> <p> Hello  <b> world </b> </p> </div>"),
+            normalized("<div><p>Hello <b> world </b></p></div>"),
+        );
+        assert_ne!(
+            normalized("<p>Hello <b>world</b></p>"),
+            normalized("<p>Hello<b>world</b></p>"),
+        );
+        assert_ne!(
+            normalized("<p><b>Hello </b>world</p>"),
+            normalized("<p><b>Hello</b>world</p>"),
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let left = normalized("<ul><li>a</li><li>b</li></ul><p>c</p>");
+        let right = normalized("<ul><li>a</li><li>x</li></ul><p>c</p>");
+        assert_eq!(
+            first_difference(&left, &right),
+            Some(vec!["ul".into(), "li:nth-of-type(2)".into()]),
+        );
+        let right = normalized("<ul><li>a</li><li>b</li></ul>");
+        assert_eq!(first_difference(&left, &right), Some(vec![]));
+        assert_eq!(first_difference(&left, &left), None);
+    }
+
+    #[test]
+    fn lines_and_diff() {
+        let left = lines(&normalized(r#"<ul class="x"><li>a</li><li>b</li></ul>"#));
+        let right = lines(&normalized(r#"<ul class="x"><li>a</li><li>c</li></ul>"#));
+        assert_eq!(
+            diff(&left, &right),
+            " <ul class=\"x\">\n   <li>\n     \"a\"\n   <li>\n-    \"b\"\n+    \"c\"\n",
+        );
+    }
+}
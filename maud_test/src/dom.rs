@@ -0,0 +1,347 @@
+//! A read-only document tree, for looking things up in rendered HTML.
+
+use std::fmt::{self, Debug, Formatter};
+
+use crate::{
+    parse::{self, Attr, Node},
+    selector::SelectorList,
+};
+
+/// A parsed HTML document or fragment, which can be searched with CSS
+/// selectors.
+///
+/// ```rust
+/// use maud::html;
+/// use maud_test::Document;
+///
+/// let markup = html! {
+///     ul {
+///         li.item { "Apples" }
+///         li.item.sold-out { "Pears" }
+///     }
+///     p #total { "2" }
+/// };
+/// let document = Document::parse(markup);
+/// assert_eq!(document.select("li.item").len(), 2);
+/// assert_eq!(document.select_one("li:not(.sold-out)").text(), "Apples");
+/// assert_eq!(document.select_one("#total").text(), "2");
+/// ```
+///
+/// Parsing never fails. Like a browser, the parser accepts unquoted
+/// attributes and left out end tags, and keeps anything it doesn't
+/// understand as text.
+#[derive(Clone)]
+pub struct Document {
+    /// Every node in the document, with the document itself first. A node
+    /// always comes after its parent and earlier siblings.
+    nodes: Vec<NodeData>,
+}
+
+#[derive(Clone)]
+struct NodeData {
+    parent: Option<usize>,
+    children: Vec<usize>,
+    kind: NodeKind,
+}
+
+#[derive(Clone)]
+enum NodeKind {
+    Document,
+    Element {
+        name: String,
+        attrs: Vec<Attr>,
+    },
+    Text(String),
+    /// A comment or doctype, which selectors and text skip over.
+    Other,
+}
+
+impl Document {
+    /// Parses some HTML, such as a rendered
+    /// [`Markup`](https://docs.rs/maud/*/maud/type.Markup.html).
+    pub fn parse(html: impl AsRef<str>) -> Self {
+        let mut document = Self {
+            nodes: vec![NodeData {
+                parent: None,
+                children: Vec::new(),
+                kind: NodeKind::Document,
+            }],
+        };
+        document.add_children(0, parse::parse(html.as_ref()));
+        document
+    }
+
+    fn add_children(&mut self, parent: usize, nodes: Vec<Node>) {
+        for node in nodes {
+            let id = self.nodes.len();
+            self.nodes[parent].children.push(id);
+            let (kind, children) = match node {
+                Node::Element(element) => (
+                    NodeKind::Element {
+                        name: element.name,
+                        attrs: element.attrs,
+                    },
+                    element.children,
+                ),
+                Node::Text(text) => (NodeKind::Text(text), Vec::new()),
+                Node::Comment(_) | Node::Doctype(_) => (NodeKind::Other, Vec::new()),
+            };
+            self.nodes.push(NodeData {
+                parent: Some(parent),
+                children: Vec::new(),
+                kind,
+            });
+            self.add_children(id, children);
+        }
+    }
+
+    /// Returns every element that matches a CSS selector, in document order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selector isn't valid, or uses a feature that isn't
+    /// supported. See the [crate documentation](crate#selectors) for what is.
+    pub fn select(&self, selector: &str) -> Vec<Element<'_>> {
+        self.select_in(0, selector)
+    }
+
+    /// Returns the one element that matches a CSS selector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no elements match, or more than one does, or the selector
+    /// isn't valid.
+    pub fn select_one(&self, selector: &str) -> Element<'_> {
+        self.select_one_in(0, selector)
+    }
+
+    /// Returns the text in the document. See [`Element::text`].
+    pub fn text(&self) -> String {
+        self.text_of(0)
+    }
+
+    fn select_in(&self, scope: usize, selector: &str) -> Vec<Element<'_>> {
+        let selector = SelectorList::parse(selector)
+            .unwrap_or_else(|error| panic!("invalid selector `{selector}`: {error}"));
+        let mut found = Vec::new();
+        self.for_each_descendant(scope, &mut |id| {
+            if selector.matches(self, id) {
+                found.push(Element { document: self, id });
+            }
+        });
+        found
+    }
+
+    fn select_one_in(&self, scope: usize, selector: &str) -> Element<'_> {
+        match self.select_in(scope, selector).as_slice() {
+            [element] => *element,
+            found => panic!(
+                "expected one element to match `{selector}`, but found {}",
+                found.len()
+            ),
+        }
+    }
+
+    /// Calls a function on each element inside a node, in document order.
+    fn for_each_descendant(&self, id: usize, f: &mut impl FnMut(usize)) {
+        for &child in &self.nodes[id].children {
+            if self.is_element(child) {
+                f(child);
+                self.for_each_descendant(child, f);
+            }
+        }
+    }
+
+    fn text_of(&self, id: usize) -> String {
+        fn collect(document: &Document, id: usize, text: &mut String) {
+            match &document.nodes[id].kind {
+                NodeKind::Text(t) => text.push_str(t),
+                _ => {
+                    for &child in &document.nodes[id].children {
+                        collect(document, child, text);
+                    }
+                }
+            }
+        }
+        let mut text = String::new();
+        collect(self, id, &mut text);
+        text.split_ascii_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn is_element(&self, id: usize) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Element { .. })
+    }
+
+    pub(crate) fn name(&self, id: usize) -> &str {
+        match &self.nodes[id].kind {
+            NodeKind::Element { name, .. } => name,
+            _ => "",
+        }
+    }
+
+    pub(crate) fn attrs(&self, id: usize) -> &[Attr] {
+        match &self.nodes[id].kind {
+            NodeKind::Element { attrs, .. } => attrs,
+            _ => &[],
+        }
+    }
+
+    pub(crate) fn attr(&self, id: usize, name: &str) -> Option<&str> {
+        self.attrs(id)
+            .iter()
+            .find(|attr| attr.name.eq_ignore_ascii_case(name))
+            .map(|attr| attr.value.as_deref().unwrap_or_default())
+    }
+
+    /// Returns the parent of a node, if it's an element.
+    pub(crate) fn parent_element(&self, id: usize) -> Option<usize> {
+        self.nodes[id]
+            .parent
+            .filter(|&parent| self.is_element(parent))
+    }
+
+    /// Returns the elements next to an element, including the element itself.
+    pub(crate) fn element_siblings(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        let parent = self.nodes[id].parent.unwrap_or_default();
+        self.nodes[parent]
+            .children
+            .iter()
+            .copied()
+            .filter(|&sibling| self.is_element(sibling))
+    }
+
+    /// Returns whether an element has nothing in it, not even text.
+    pub(crate) fn is_empty(&self, id: usize) -> bool {
+        self.nodes[id].children.iter().all(|&child| {
+            matches!(&self.nodes[child].kind, NodeKind::Other)
+                || matches!(&self.nodes[child].kind, NodeKind::Text(text) if text.is_empty())
+        })
+    }
+}
+
+impl Debug for Document {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.nodes[0]
+                    .children
+                    .iter()
+                    .filter(|&&id| self.is_element(id))
+                    .map(|&id| Element { document: self, id }),
+            )
+            .finish()
+    }
+}
+
+/// An element in a [`Document`].
+#[derive(Clone, Copy)]
+pub struct Element<'a> {
+    document: &'a Document,
+    id: usize,
+}
+
+impl<'a> Element<'a> {
+    /// Returns the name of the element, in lowercase.
+    pub fn name(&self) -> &'a str {
+        self.document.name(self.id)
+    }
+
+    /// Returns the value of an attribute, or `None` if the element doesn't
+    /// have it.
+    ///
+    /// An attribute written without a value, as in `<input disabled>`, has
+    /// an empty value.
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.document.attr(self.id, name)
+    }
+
+    /// Returns the names and values of all the attributes, in the order
+    /// they're written.
+    pub fn attrs(&self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.document.attrs(self.id).iter().map(|attr| {
+            (
+                attr.name.as_str(),
+                attr.value.as_deref().unwrap_or_default(),
+            )
+        })
+    }
+
+    /// Returns whether the element has a class.
+    pub fn has_class(&self, class: &str) -> bool {
+        self.attr("class")
+            .is_some_and(|classes| classes.split_ascii_whitespace().any(|c| c == class))
+    }
+
+    /// Returns the text inside the element.
+    ///
+    /// As with `textContent` in the browser, the text in child elements is
+    /// joined without anything in between. Runs of whitespace are collapsed
+    /// into a single space, and whitespace at the start and end is removed.
+    /// Character references, as in `&amp;`, are decoded.
+    pub fn text(&self) -> String {
+        self.document.text_of(self.id)
+    }
+
+    /// Returns whether the element matches a CSS selector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selector isn't valid.
+    pub fn matches(&self, selector: &str) -> bool {
+        SelectorList::parse(selector)
+            .unwrap_or_else(|error| panic!("invalid selector `{selector}`: {error}"))
+            .matches(self.document, self.id)
+    }
+
+    /// Returns every element inside this one that matches a CSS selector, in
+    /// document order.
+    ///
+    /// As with `querySelectorAll` in the browser, the whole selector is
+    /// matched against the document, so `div p` finds a `p` in this element
+    /// even if this element is the `div`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selector isn't valid.
+    pub fn select(&self, selector: &str) -> Vec<Element<'a>> {
+        self.document.select_in(self.id, selector)
+    }
+
+    /// Returns the one element inside this one that matches a CSS selector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no elements match, or more than one does, or the selector
+    /// isn't valid.
+    pub fn select_one(&self, selector: &str) -> Element<'a> {
+        self.document.select_one_in(self.id, selector)
+    }
+
+    /// Returns the element that contains this one, if any.
+    pub fn parent(&self) -> Option<Element<'a>> {
+        let document = self.document;
+        document
+            .parent_element(self.id)
+            .map(|id| Element { document, id })
+    }
+
+    /// Returns the elements directly inside this one.
+    pub fn children(&self) -> impl Iterator<Item = Element<'a>> + 'a {
+        let document = self.document;
+        document.nodes[self.id]
+            .children
+            .iter()
+            .filter(move |&&id| document.is_element(id))
+            .map(move |&id| Element { document, id })
+    }
+}
+
+impl Debug for Element<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "<{}", self.name())?;
+        for (name, value) in self.attrs() {
+            write!(f, " {name}={value:?}")?;
+        }
+        write!(f, ">")
+    }
+}
@@ -0,0 +1,137 @@
+//! Test helpers for HTML rendered by Maud.
+//!
+//! Comparing rendered markup with a string works, but the test breaks when
+//! something that doesn't matter changes, like the order of the attributes.
+//! [`assert_html_eq!`] parses both sides first, and only fails if they differ
+//! in a way that shows up on the page:
+//!
+//! ```rust
+//! use maud::html;
+//! use maud_test::assert_html_eq;
+//!
+//! let markup = html! {
+//!     #[allow(missing_label)]
+//!     input.big.red type="checkbox" checked;
+//! };
+//! assert_html_eq!(markup, r#"<input checked type="checkbox" class="red big">"#);
+//! ```
+//!
+//! When they do differ, the message shows where, along with a diff of the
+//! two documents:
+//!
+//! ```text
+//! assertion `left == right` failed: HTML differs at `ul > li:nth-of-type(2)`
+//! diff (- left, + right):
+//!  <ul>
+//!    <li>
+//!      "Apples"
+//!    <li>
+//! -    "Pears"
+//! +    "Plums"
+//! ```
+//!
+//! For checking parts of a page, [`Document`] finds elements with CSS
+//! selectors, and reads their text and attributes:
+//!
+//! ```rust
+//! use maud::html;
+//! use maud_test::Document;
+//!
+//! let markup = html! {
+//!     a.nav href="/" { "Home" }
+//!     a.nav.active href="/about" { "About" }
+//! };
+//! let document = Document::parse(markup);
+//! let active = document.select_one("a.nav.active");
+//! assert_eq!(active.attr("href"), Some("/about"));
+//! assert_eq!(active.text(), "About");
+//! ```
+//!
+//! # Selectors
+//!
+//! Most of the selectors from CSS level 3 are supported:
+//!
+//! * Type and universal selectors: `li`, `*`
+//! * IDs and classes: `#total`, `.item`
+//! * Attributes: `[href]`, `[type=checkbox]`, `[rel~=noopener]`,
+//!   `[lang|=en]`, `[href^=https]`, `[href$=".pdf"]`, `[title*=pony]`
+//! * Combinators: `nav a`, `ul > li`, `h2 + p`, `h2 ~ p`
+//! * Pseudo-classes: `:first-child`, `:last-child`, `:only-child`,
+//!   `:nth-child(2n+1)`, `:nth-last-child(...)`, `:first-of-type`,
+//!   `:last-of-type`, `:only-of-type`, `:nth-of-type(...)`,
+//!   `:nth-last-of-type(...)`, `:empty`, `:not(...)`
+//! * Lists of selectors: `h1, h2`
+//!
+//! Pseudo-classes that depend on the state of a browser, like `:hover`, and
+//! pseudo-elements, like `::before`, aren't supported.
+
+mod compare;
+mod dom;
+mod selector;
+
+pub use dom::{Document, Element};
+
+use maud_html as parse;
+use std::fmt::{self, Write};
+
+/// Asserts that two pieces of HTML are the same, ignoring differences that
+/// don't show up on the page.
+///
+/// Each side can be a [`Markup`](https://docs.rs/maud/*/maud/type.Markup.html)
+/// or a string. Before comparing them:
+///
+/// * Attributes are sorted by name, and the classes in a `class` attribute
+///   are sorted too. An attribute with an empty value, as in `disabled=""`,
+///   is the same as one without, as in `disabled`.
+/// * Runs of whitespace in text are collapsed into a single space, and
+///   whitespace next to a block element, like `div` or `li`, is removed.
+///   Whitespace next to an inline element, like `b` or `a`, shows up on the
+///   page, so it's kept. None of this is done inside `pre`, `textarea`,
+///   `script` and `style`.
+/// * Character references, as in `&amp;`, are decoded.
+/// * Comments are removed.
+///
+/// Like [`assert_eq!`], a custom message can be given after the two sides.
+#[macro_export]
+macro_rules! assert_html_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__assert_html_eq(
+            ::core::convert::AsRef::<str>::as_ref(&$left),
+            ::core::convert::AsRef::<str>::as_ref(&$right),
+            ::core::option::Option::None,
+        )
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::__assert_html_eq(
+            ::core::convert::AsRef::<str>::as_ref(&$left),
+            ::core::convert::AsRef::<str>::as_ref(&$right),
+            ::core::option::Option::Some(::core::format_args!($($arg)+)),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_html_eq(left: &str, right: &str, message: Option<fmt::Arguments>) {
+    let left = compare::normalize(parse::parse(left), false, false);
+    let right = compare::normalize(parse::parse(right), false, false);
+    let Some(path) = compare::first_difference(&left, &right) else {
+        return;
+    };
+
+    let mut output = String::from("assertion `left == right` failed: ");
+    if let Some(message) = message {
+        let _ = writeln!(output, "{message}");
+    }
+    if path.is_empty() {
+        output.push_str("HTML differs at the top level\n");
+    } else {
+        let _ = writeln!(output, "HTML differs at `{}`", path.join(" > "));
+    }
+    output.push_str("diff (- left, + right):\n");
+    output.push_str(&compare::diff(
+        &compare::lines(&left),
+        &compare::lines(&right),
+    ));
+    panic!("{}", output.trim_end());
+}
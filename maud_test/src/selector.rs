@@ -0,0 +1,441 @@
+//! Parsing and matching CSS selectors.
+
+use crate::dom::Document;
+
+/// A comma-separated list of selectors, which matches an element if any of
+/// them do.
+#[derive(Debug)]
+pub struct SelectorList(Vec<Complex>);
+
+/// A chain of compound selectors joined by combinators, as in `ul > li.item`.
+#[derive(Debug)]
+struct Complex {
+    /// The selector that the element itself has to match.
+    subject: Compound,
+    /// The rest of the chain, from right to left. Each combinator says how
+    /// the compound selector before it relates to the one after.
+    ancestors: Vec<(Combinator, Compound)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Combinator {
+    /// `a b`
+    Descendant,
+    /// `a > b`
+    Child,
+    /// `a + b`
+    NextSibling,
+    /// `a ~ b`
+    SubsequentSibling,
+}
+
+/// Simple selectors that all have to match the same element, as in
+/// `li.item:first-child`.
+#[derive(Debug, Default)]
+struct Compound {
+    name: Option<String>,
+    simple: Vec<Simple>,
+}
+
+#[derive(Debug)]
+enum Simple {
+    Id(String),
+    Class(String),
+    Attr {
+        name: String,
+        matcher: Option<(AttrOp, String)>,
+    },
+    NthChild(Nth),
+    NthLastChild(Nth),
+    NthOfType(Nth),
+    NthLastOfType(Nth),
+    OnlyChild,
+    OnlyOfType,
+    Empty,
+    Not(SelectorList),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum AttrOp {
+    /// `[a=b]`
+    Equals,
+    /// `[a~=b]`
+    Includes,
+    /// `[a|=b]`
+    DashMatch,
+    /// `[a^=b]`
+    Prefix,
+    /// `[a$=b]`
+    Suffix,
+    /// `[a*=b]`
+    Substring,
+}
+
+/// An `An+B` expression, which matches the positions `B`, `A+B`, `2A+B` and
+/// so on, counting from 1.
+#[derive(Debug, Clone, Copy)]
+struct Nth {
+    a: i64,
+    b: i64,
+}
+
+impl SelectorList {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parser = Parser { rest: input };
+        let list = parser.selector_list()?;
+        parser.skip_whitespace();
+        match parser.rest.chars().next() {
+            None => Ok(list),
+            Some(c) => Err(format!("unexpected `{c}`")),
+        }
+    }
+
+    pub fn matches(&self, document: &Document, id: usize) -> bool {
+        self.0.iter().any(|complex| complex.matches(document, id))
+    }
+}
+
+impl Complex {
+    fn matches(&self, document: &Document, id: usize) -> bool {
+        self.subject.matches(document, id) && matches_chain(document, id, &self.ancestors)
+    }
+}
+
+/// Checks the rest of a chain, given that `id` matched the compound selector
+/// before it.
+fn matches_chain(document: &Document, id: usize, chain: &[(Combinator, Compound)]) -> bool {
+    let Some(((combinator, compound), rest)) = chain.split_first() else {
+        return true;
+    };
+    let matches = |candidate: usize| {
+        compound.matches(document, candidate) && matches_chain(document, candidate, rest)
+    };
+    match combinator {
+        Combinator::Descendant => {
+            let mut ancestor = document.parent_element(id);
+            while let Some(candidate) = ancestor {
+                if matches(candidate) {
+                    return true;
+                }
+                ancestor = document.parent_element(candidate);
+            }
+            false
+        }
+        Combinator::Child => document.parent_element(id).is_some_and(matches),
+        Combinator::NextSibling => document
+            .element_siblings(id)
+            .take_while(|&sibling| sibling != id)
+            .last()
+            .is_some_and(matches),
+        Combinator::SubsequentSibling => document
+            .element_siblings(id)
+            .take_while(|&sibling| sibling != id)
+            .any(matches),
+    }
+}
+
+impl Compound {
+    fn matches(&self, document: &Document, id: usize) -> bool {
+        self.name
+            .as_ref()
+            .is_none_or(|name| document.name(id).eq_ignore_ascii_case(name))
+            && self
+                .simple
+                .iter()
+                .all(|simple| simple.matches(document, id))
+    }
+}
+
+impl Simple {
+    fn matches(&self, document: &Document, id: usize) -> bool {
+        match self {
+            Self::Id(expected) => document.attr(id, "id") == Some(expected),
+            Self::Class(class) => document
+                .attr(id, "class")
+                .is_some_and(|classes| classes.split_ascii_whitespace().any(|c| c == class)),
+            Self::Attr { name, matcher } => {
+                let Some(value) = document.attr(id, name) else {
+                    return false;
+                };
+                let Some((op, expected)) = matcher else {
+                    return true;
+                };
+                match op {
+                    AttrOp::Equals => value == expected,
+                    AttrOp::Includes => value.split_ascii_whitespace().any(|v| v == expected),
+                    AttrOp::DashMatch => {
+                        value == expected
+                            || value
+                                .strip_prefix(expected.as_str())
+                                .is_some_and(|rest| rest.starts_with('-'))
+                    }
+                    AttrOp::Prefix => !expected.is_empty() && value.starts_with(expected.as_str()),
+                    AttrOp::Suffix => !expected.is_empty() && value.ends_with(expected.as_str()),
+                    AttrOp::Substring => !expected.is_empty() && value.contains(expected.as_str()),
+                }
+            }
+            Self::NthChild(nth) => nth.matches(position(document, id, false, false)),
+            Self::NthLastChild(nth) => nth.matches(position(document, id, true, false)),
+            Self::NthOfType(nth) => nth.matches(position(document, id, false, true)),
+            Self::NthLastOfType(nth) => nth.matches(position(document, id, true, true)),
+            Self::OnlyChild => document.element_siblings(id).count() == 1,
+            Self::OnlyOfType => {
+                let name = document.name(id);
+                document
+                    .element_siblings(id)
+                    .filter(|&sibling| document.name(sibling) == name)
+                    .count()
+                    == 1
+            }
+            Self::Empty => document.is_empty(id),
+            Self::Not(list) => !list.matches(document, id),
+        }
+    }
+}
+
+/// Returns the position of an element among its siblings, counting from 1.
+fn position(document: &Document, id: usize, from_end: bool, of_type: bool) -> i64 {
+    let siblings = document
+        .element_siblings(id)
+        .filter(|&sibling| !of_type || document.name(sibling) == document.name(id))
+        .collect::<Vec<_>>();
+    let index = siblings.iter().position(|&sibling| sibling == id).unwrap();
+    let index = if from_end {
+        siblings.len() - 1 - index
+    } else {
+        index
+    };
+    index as i64 + 1
+}
+
+impl Nth {
+    fn matches(self, position: i64) -> bool {
+        match self.a {
+            0 => position == self.b,
+            a => {
+                let n = position - self.b;
+                n % a == 0 && n / a >= 0
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    fn selector_list(&mut self) -> Result<SelectorList, String> {
+        let mut list = vec![self.complex()?];
+        loop {
+            self.skip_whitespace();
+            match self.rest.strip_prefix(',') {
+                Some(rest) => {
+                    self.rest = rest;
+                    list.push(self.complex()?);
+                }
+                None => return Ok(SelectorList(list)),
+            }
+        }
+    }
+
+    fn complex(&mut self) -> Result<Complex, String> {
+        self.skip_whitespace();
+        let mut compounds = vec![self.compound()?];
+        let mut combinators = Vec::new();
+        loop {
+            let had_whitespace = self.skip_whitespace();
+            let combinator = match self.rest.chars().next() {
+                Some('>') => Combinator::Child,
+                Some('+') => Combinator::NextSibling,
+                Some('~') => Combinator::SubsequentSibling,
+                None | Some(',' | ')') => break,
+                Some(_) if had_whitespace => Combinator::Descendant,
+                Some(c) => return Err(format!("unexpected `{c}`")),
+            };
+            if !matches!(combinator, Combinator::Descendant) {
+                self.rest = &self.rest[1..];
+                self.skip_whitespace();
+            }
+            combinators.push(combinator);
+            compounds.push(self.compound()?);
+        }
+
+        let subject = compounds.pop().unwrap();
+        let ancestors = combinators
+            .into_iter()
+            .rev()
+            .zip(compounds.into_iter().rev())
+            .collect();
+        Ok(Complex { subject, ancestors })
+    }
+
+    fn compound(&mut self) -> Result<Compound, String> {
+        let mut compound = Compound::default();
+        let star = self.rest.starts_with('*');
+        if star {
+            self.rest = &self.rest[1..];
+        } else if self.rest.starts_with(is_name_char) {
+            compound.name = Some(self.name()?);
+        }
+        while let Some(c) = self.rest.chars().next() {
+            let simple = match c {
+                '#' => {
+                    self.rest = &self.rest[1..];
+                    Simple::Id(self.name()?)
+                }
+                '.' => {
+                    self.rest = &self.rest[1..];
+                    Simple::Class(self.name()?)
+                }
+                '[' => {
+                    self.rest = &self.rest[1..];
+                    self.attr()?
+                }
+                ':' => {
+                    self.rest = &self.rest[1..];
+                    self.pseudo_class()?
+                }
+                _ => break,
+            };
+            compound.simple.push(simple);
+        }
+        if !star && compound.name.is_none() && compound.simple.is_empty() {
+            return Err(match self.rest.chars().next() {
+                Some(c) => format!("unexpected `{c}`"),
+                None => "unexpected end of selector".into(),
+            });
+        }
+        Ok(compound)
+    }
+
+    fn attr(&mut self) -> Result<Simple, String> {
+        self.skip_whitespace();
+        let name = self.name()?;
+        self.skip_whitespace();
+        let op = [
+            ("=", AttrOp::Equals),
+            ("~=", AttrOp::Includes),
+            ("|=", AttrOp::DashMatch),
+            ("^=", AttrOp::Prefix),
+            ("$=", AttrOp::Suffix),
+            ("*=", AttrOp::Substring),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.rest.starts_with(token));
+        let matcher = match op {
+            Some((token, op)) => {
+                self.rest = self.rest[token.len()..].trim_start();
+                let value = match self.rest.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let end = self.rest[1..].find(quote).ok_or("unterminated string")? + 1;
+                        let value = self.rest[1..end].to_string();
+                        self.rest = &self.rest[end + 1..];
+                        value
+                    }
+                    _ => self.name()?,
+                };
+                self.skip_whitespace();
+                Some((op, value))
+            }
+            None => None,
+        };
+        self.rest = self.rest.strip_prefix(']').ok_or("expected `]`")?;
+        Ok(Simple::Attr { name, matcher })
+    }
+
+    fn pseudo_class(&mut self) -> Result<Simple, String> {
+        let name = self.name()?.to_ascii_lowercase();
+        let first = Nth { a: 0, b: 1 };
+        let simple = match name.as_str() {
+            "first-child" => Simple::NthChild(first),
+            "last-child" => Simple::NthLastChild(first),
+            "only-child" => Simple::OnlyChild,
+            "first-of-type" => Simple::NthOfType(first),
+            "last-of-type" => Simple::NthLastOfType(first),
+            "only-of-type" => Simple::OnlyOfType,
+            "empty" => Simple::Empty,
+            "nth-child" => Simple::NthChild(self.arguments(Self::nth)?),
+            "nth-last-child" => Simple::NthLastChild(self.arguments(Self::nth)?),
+            "nth-of-type" => Simple::NthOfType(self.arguments(Self::nth)?),
+            "nth-last-of-type" => Simple::NthLastOfType(self.arguments(Self::nth)?),
+            "not" => Simple::Not(self.arguments(Self::selector_list)?),
+            _ => return Err(format!("unsupported pseudo-class `:{name}`")),
+        };
+        Ok(simple)
+    }
+
+    /// Parses the arguments to a pseudo-class, in parentheses.
+    fn arguments<T>(&mut self, parse: fn(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        self.rest = self.rest.strip_prefix('(').ok_or("expected `(`")?;
+        self.skip_whitespace();
+        let value = parse(self)?;
+        self.skip_whitespace();
+        self.rest = self.rest.strip_prefix(')').ok_or("expected `)`")?;
+        Ok(value)
+    }
+
+    fn nth(&mut self) -> Result<Nth, String> {
+        let end = self.rest.find(')').unwrap_or(self.rest.len());
+        let text = self.rest[..end]
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        let invalid = || format!("invalid `An+B` expression `{}`", self.rest[..end].trim());
+        let nth = match text.as_str() {
+            "odd" => Nth { a: 2, b: 1 },
+            "even" => Nth { a: 2, b: 0 },
+            _ => match text.split_once('n') {
+                Some((a, b)) => Nth {
+                    a: match a {
+                        "" | "+" => 1,
+                        "-" => -1,
+                        a => a.parse().map_err(|_| invalid())?,
+                    },
+                    b: match b {
+                        "" => 0,
+                        b if b.starts_with(['+', '-']) => {
+                            b.trim_start_matches('+').parse().map_err(|_| invalid())?
+                        }
+                        _ => return Err(invalid()),
+                    },
+                },
+                None => Nth {
+                    a: 0,
+                    b: text.parse().map_err(|_| invalid())?,
+                },
+            },
+        };
+        self.rest = &self.rest[end..];
+        Ok(nth)
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let end = self
+            .rest
+            .find(|c: char| !is_name_char(c))
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err(match self.rest.chars().next() {
+                Some(c) => format!("expected a name, found `{c}`"),
+                None => "expected a name".into(),
+            });
+        }
+        let name = self.rest[..end].to_string();
+        self.rest = &self.rest[end..];
+        Ok(name)
+    }
+
+    /// Skips whitespace, returning whether there was any.
+    fn skip_whitespace(&mut self) -> bool {
+        let trimmed = self.rest.trim_start();
+        let skipped = trimmed.len() != self.rest.len();
+        self.rest = trimmed;
+        skipped
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_') || !c.is_ascii()
+}
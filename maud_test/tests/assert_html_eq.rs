@@ -0,0 +1,137 @@
+use std::panic;
+
+use maud::{PreEscaped, html};
+use maud_test::assert_html_eq;
+
+/// Returns the message that an assertion panics with.
+fn panic_message(f: impl FnOnce() + panic::UnwindSafe) -> String {
+    let payload = panic::catch_unwind(f).unwrap_err();
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().unwrap().to_string(),
+    }
+}
+
+#[test]
+fn attribute_order() {
+    assert_html_eq!(
+        html! { a href="/" title="Home" { "Home" } },
+        r#"<a title="Home" href="/">Home</a>"#,
+    );
+}
+
+#[test]
+fn class_order() {
+    assert_html_eq!(
+        html! { p.intro.big class="lead" { "Hi" } },
+        r#"<p class="big intro lead">Hi</p>"#,
+    );
+}
+
+#[test]
+fn whitespace() {
+    assert_html_eq!(
+        html! { ul { li { "Applejack" } li { "Rarity" } } },
+        r#"
+        <ul>
+            <li>
+                Applejack
+            </li>
+            <li>Rarity</li>
+        </ul>
+        "#,
+    );
+}
+
+#[test]
+fn inline_whitespace() {
+    let message = panic_message(|| {
+        assert_html_eq!("<p>Hello <b>world</b></p>", "<p>Hello<b>world</b></p>");
+    });
+    assert!(message.contains("HTML differs at `p`"), "{message}");
+}
+
+#[test]
+fn preformatted_whitespace() {
+    let message = panic_message(|| {
+        assert_html_eq!(html! { pre { "  indented" } }, "<pre>indented</pre>");
+    });
+    assert!(message.contains("HTML differs at `pre`"), "{message}");
+}
+
+#[test]
+fn empty_attributes() {
+    assert_html_eq!(
+        html! { #[allow(missing_label)] input type="checkbox" checked; },
+        r#"<input type="checkbox" checked="">"#,
+    );
+}
+
+#[test]
+fn character_references() {
+    assert_html_eq!(html! { "Fish & Chips" }, "Fish &#38; Chips");
+}
+
+#[test]
+fn strings() {
+    assert_html_eq!("<br>", String::from("<br/>"));
+    assert_html_eq!(PreEscaped("<b>Hi</b>"), "<b>Hi</b>");
+}
+
+#[test]
+fn diff() {
+    let message = panic_message(|| {
+        assert_html_eq!(
+            html! { ul { li { "Apples" } li { "Pears" } } },
+            "<ul><li>Apples</li><li>Plums</li></ul>",
+        );
+    });
+    assert_eq!(
+        message,
+        r#"assertion `left == right` failed: HTML differs at `ul > li:nth-of-type(2)`
+diff (- left, + right):
+ <ul>
+   <li>
+     "Apples"
+   <li>
+-    "Pears"
++    "Plums""#,
+    );
+}
+
+#[test]
+fn attribute_diff() {
+    let message = panic_message(|| {
+        assert_html_eq!(
+            html! { div { p.note { "Hi" } } },
+            r#"<div><p class="warning">Hi</p></div>"#,
+        );
+    });
+    assert!(message.contains("HTML differs at `div > p`\n"), "{message}",);
+    assert!(message.contains(r#"-  <p class="note">"#), "{message}");
+    assert!(message.contains(r#"+  <p class="warning">"#), "{message}");
+}
+
+#[test]
+fn missing_nodes() {
+    let message = panic_message(|| {
+        assert_html_eq!(html! { p { "Hi" } }, "<p>Hi</p><p>Bye</p>");
+    });
+    assert!(
+        message.contains("HTML differs at the top level\n"),
+        "{message}",
+    );
+    assert!(message.ends_with("+<p>\n+  \"Bye\""), "{message}");
+}
+
+#[test]
+fn custom_message() {
+    let message = panic_message(|| {
+        assert_html_eq!("<b>a</b>", "<i>a</i>", "rendering {}", "bold");
+    });
+    assert!(
+        message
+            .starts_with("assertion `left == right` failed: rendering bold\nHTML differs at `b`\n"),
+        "{message}",
+    );
+}
@@ -0,0 +1,178 @@
+use maud::html;
+use maud_test::Document;
+
+fn names(elements: &[maud_test::Element]) -> Vec<String> {
+    elements
+        .iter()
+        .map(|element| match element.attr("id") {
+            Some(id) => format!("{}#{id}", element.name()),
+            None => element.name().to_string(),
+        })
+        .collect()
+}
+
+fn page() -> Document {
+    Document::parse(html! {
+        nav {
+            a #home href="/" { "Home" }
+            a #about.active href="/about" rel="noopener help" { "About" }
+        }
+        main {
+            h2 #first { "Fruit" }
+            p #intro lang="en-GB" { "Some  fruit:" }
+            ul {
+                li.item #apples { "Apples" }
+                li.item.sold-out #pears { "Pears" }
+                li.item #plums { "Plums" }
+            }
+            p #outro { }
+            p #total data-count="3" { "3" }
+        }
+    })
+}
+
+#[test]
+fn types_ids_and_classes() {
+    let page = page();
+    assert_eq!(page.select("li").len(), 3);
+    assert_eq!(page.select("li.item").len(), 3);
+    assert_eq!(names(&page.select(".sold-out")), ["li#pears"]);
+    assert_eq!(names(&page.select("#total")), ["p#total"]);
+    assert_eq!(names(&page.select("a.active#about")), ["a#about"]);
+    assert_eq!(page.select("*").len(), 12);
+    assert!(page.select("table").is_empty());
+}
+
+#[test]
+fn attributes() {
+    let page = page();
+    assert_eq!(names(&page.select("[rel]")), ["a#about"]);
+    assert_eq!(names(&page.select("a[href='/']")), ["a#home"]);
+    assert_eq!(names(&page.select("[rel~=help]")), ["a#about"]);
+    assert_eq!(names(&page.select("[lang|=en]")), ["p#intro"]);
+    assert_eq!(names(&page.select(r#"[href^="/a"]"#)), ["a#about"]);
+    assert_eq!(names(&page.select("[href$=out]")), ["a#about"]);
+    assert_eq!(names(&page.select("[href*=bo]")), ["a#about"]);
+}
+
+#[test]
+fn combinators() {
+    let page = page();
+    assert_eq!(page.select("main li").len(), 3);
+    assert_eq!(page.select("main > li").len(), 0);
+    assert_eq!(names(&page.select("h2 + p")), ["p#intro"]);
+    assert_eq!(
+        names(&page.select("h2 ~ p")),
+        ["p#intro", "p#outro", "p#total"]
+    );
+    assert_eq!(names(&page.select("ul>li+li")), ["li#pears", "li#plums"]);
+}
+
+#[test]
+fn pseudo_classes() {
+    let page = page();
+    assert_eq!(names(&page.select("li:first-child")), ["li#apples"]);
+    assert_eq!(names(&page.select("li:last-child")), ["li#plums"]);
+    assert_eq!(names(&page.select("li:nth-child(2)")), ["li#pears"]);
+    assert_eq!(
+        names(&page.select("li:nth-child(odd)")),
+        ["li#apples", "li#plums"]
+    );
+    assert_eq!(
+        names(&page.select("li:nth-child(-n + 2)")),
+        ["li#apples", "li#pears"]
+    );
+    assert_eq!(names(&page.select("li:nth-last-child(1)")), ["li#plums"]);
+    assert_eq!(names(&page.select("p:first-of-type")), ["p#intro"]);
+    assert_eq!(names(&page.select("main > p:last-of-type")), ["p#total"]);
+    assert_eq!(names(&page.select("main p:nth-of-type(2)")), ["p#outro"]);
+    assert_eq!(
+        names(&page.select("main > :only-of-type")),
+        ["h2#first", "ul"]
+    );
+    assert_eq!(names(&page.select(":empty")), ["p#outro"]);
+    assert_eq!(
+        names(&page.select("li:not(.sold-out, :first-child)")),
+        ["li#plums"]
+    );
+    assert!(page.select(":only-child").is_empty());
+}
+
+#[test]
+fn selector_lists() {
+    let page = page();
+    assert_eq!(names(&page.select("#total, h2")), ["h2#first", "p#total"]);
+}
+
+#[test]
+fn text_and_attributes() {
+    let page = page();
+    assert_eq!(page.select_one("#total").text(), "3");
+    assert_eq!(page.select_one("#intro").text(), "Some fruit:");
+    assert_eq!(page.select_one("ul").text(), "ApplesPearsPlums");
+    assert_eq!(page.select_one("#total").attr("data-count"), Some("3"));
+    assert_eq!(page.select_one("#total").attr("title"), None);
+    let about = page.select_one("#about");
+    assert!(about.has_class("active"));
+    assert!(!about.has_class("act"));
+    assert_eq!(
+        about.attrs().collect::<Vec<_>>(),
+        [
+            ("class", "active"),
+            ("id", "about"),
+            ("href", "/about"),
+            ("rel", "noopener help"),
+        ],
+    );
+}
+
+#[test]
+fn navigation() {
+    let page = page();
+    let ul = page.select_one("ul");
+    assert_eq!(ul.parent().unwrap().name(), "main");
+    assert!(page.select_one("nav").parent().is_none());
+    assert_eq!(ul.children().count(), 3);
+    assert_eq!(
+        names(&ul.select("li:not(.sold-out)")),
+        ["li#apples", "li#plums"]
+    );
+    assert_eq!(ul.select_one(".sold-out").text(), "Pears");
+    assert!(ul.select("ul").is_empty());
+    assert!(ul.select_one("#apples").matches("main li:first-child"));
+    assert!(!ul.select_one("#pears").matches("main li:first-child"));
+}
+
+#[test]
+fn character_references() {
+    let page = Document::parse(html! { p title="a & b" { "Fish & Chips" } });
+    assert_eq!(page.select_one("p").text(), "Fish & Chips");
+    assert_eq!(page.select_one("p").attr("title"), Some("a & b"));
+    assert_eq!(page.text(), "Fish & Chips");
+}
+
+#[test]
+#[should_panic(expected = "expected one element to match `li`, but found 3")]
+fn select_one_many() {
+    page().select_one("li");
+}
+
+#[test]
+#[should_panic(expected = "expected one element to match `table`, but found 0")]
+fn select_one_none() {
+    page().select_one("table");
+}
+
+#[test]
+#[should_panic(expected = "invalid selector `li:hover`: unsupported pseudo-class `:hover`")]
+fn unsupported_selector() {
+    page().select("li:hover");
+}
+
+#[test]
+fn invalid_selectors() {
+    for selector in ["", "li >", "[href", "li:nth-child(x)", "a..b", "a, ", "#"] {
+        let result = std::panic::catch_unwind(|| page().select(selector).len());
+        assert!(result.is_err(), "{selector:?} should be invalid");
+    }
+}